@@ -4,31 +4,14 @@
 use thiserror::Error;
 
 use crate::{
-	util::to_url_array_conditional_convert,
+	error::Result as CrateResult,
+	util::{from_json_str, to_url_array_conditional_convert},
 	AcceptedActions,
 	AcceptedCategories,
 	ActionKind,
 	Category,
 };
 
-// The API names for categories
-const SPONSOR_NAME: &str = "sponsor";
-const UNPAID_SELF_PROMOTION_NAME: &str = "selfpromo";
-const INTERACTION_REMINDER_NAME: &str = "interaction";
-const HIGHLIGHT_NAME: &str = "poi_highlight";
-const INTERMISSION_INTRO_ANIMATION_NAME: &str = "intro";
-const ENDCARDS_CREDITS_NAME: &str = "outro";
-const PREVIEW_RECAP_NAME: &str = "preview";
-const NON_MUSIC_NAME: &str = "music_offtopic";
-const FILLER_TANGENT_NAME: &str = "filler";
-const EXCLUSIVE_ACCESS_NAME: &str = "exclusive_access";
-
-// The API names for actions
-const ACTION_SKIP_NAME: &str = "skip";
-const ACTION_MUTE_NAME: &str = "mute";
-const ACTION_POINT_OF_INTEREST_NAME: &str = "poi";
-const ACTION_FULL_VIDEO_NAME: &str = "full";
-
 /// A value received from the API is not recognized.
 ///
 /// If encountering this, it's likely the library version is out of date with
@@ -42,64 +25,94 @@ pub(crate) struct UnknownValueError {
 	value: String,
 }
 
+/// Declares the conversions between a type's values, their flags, and their API
+/// names, all from a single list.
+///
+/// This keeps everything in sync when adding a new value - the generated
+/// `match` on the values is exhaustive, so forgetting to add one here is a
+/// compile error.
+macro_rules! api_name_mappings {
+	(
+		$type_name:literal,
+		$value_type:ident => $flags_type:ident,
+		$to_value:ident,
+		$from_value:ident,
+		$to_flag:ident,
+		$values:ident,
+		$pairs:ident,
+		[$($variant:ident, $flag:ident => $name:literal),+ $(,)?]
+	) => {
+		pub(crate) fn $to_value(name: &str) -> Result<$value_type, UnknownValueError> {
+			match name {
+				$($name => Ok($value_type::$variant),)+
+				unknown_value => Err(UnknownValueError {
+					r#type: $type_name.to_owned(),
+					value: unknown_value.to_owned(),
+				}),
+			}
+		}
+
+		pub(crate) fn $from_value(value: $value_type) -> &'static str {
+			match value {
+				$($value_type::$variant => $name,)+
+			}
+		}
+
+		pub(crate) fn $to_flag(value: $value_type) -> $flags_type {
+			match value {
+				$($value_type::$variant => $flags_type::$flag,)+
+			}
+		}
+
+		pub(crate) const $values: &[$value_type] = &[$($value_type::$variant,)+];
+
+		const $pairs: &[($flags_type, &str)] = &[$(($flags_type::$flag, $name),)+];
+	};
+}
+
 // API value conversion functions. The goal here is to make it so everything
 // else in the library need not interface with raw category names.
-pub(crate) fn convert_to_category(category: &str) -> Result<Category, UnknownValueError> {
-	match category {
-		SPONSOR_NAME => Ok(Category::Sponsor),
-		UNPAID_SELF_PROMOTION_NAME => Ok(Category::UnpaidSelfPromotion),
-		INTERACTION_REMINDER_NAME => Ok(Category::InteractionReminder),
-		HIGHLIGHT_NAME => Ok(Category::Highlight),
-		INTERMISSION_INTRO_ANIMATION_NAME => Ok(Category::IntermissionIntroAnimation),
-		ENDCARDS_CREDITS_NAME => Ok(Category::EndcardsCredits),
-		PREVIEW_RECAP_NAME => Ok(Category::PreviewRecap),
-		NON_MUSIC_NAME => Ok(Category::NonMusic),
-		FILLER_TANGENT_NAME => Ok(Category::FillerTangent),
-		EXCLUSIVE_ACCESS_NAME => Ok(Category::ExclusiveAccess),
-		unknown_value => Err(UnknownValueError {
-			r#type: "category".to_owned(),
-			value: unknown_value.to_owned(),
-		}),
-	}
-}
+//
+// The API names are according to https://wiki.sponsor.ajay.app/w/Types
+api_name_mappings!(
+	"category",
+	Category => AcceptedCategories,
+	convert_to_category,
+	convert_from_category,
+	convert_category_to_bitflag,
+	CATEGORIES,
+	CATEGORY_PAIRS,
+	[
+		Sponsor, SPONSOR => "sponsor",
+		UnpaidSelfPromotion, UNPAID_SELF_PROMOTION => "selfpromo",
+		InteractionReminder, INTERACTION_REMINDER => "interaction",
+		Highlight, HIGHLIGHT => "poi_highlight",
+		IntermissionIntroAnimation, INTERMISSION_INTRO_ANIMATION => "intro",
+		EndcardsCredits, ENDCARDS_CREDITS => "outro",
+		PreviewRecap, PREVIEW_RECAP => "preview",
+		NonMusic, NON_MUSIC => "music_offtopic",
+		FillerTangent, FILLER_TANGENT => "filler",
+		ExclusiveAccess, EXCLUSIVE_ACCESS => "exclusive_access",
+	]
+);
 
-pub(crate) fn convert_to_action_kind(action_type: &str) -> Result<ActionKind, UnknownValueError> {
-	match action_type {
-		ACTION_SKIP_NAME => Ok(ActionKind::Skip),
-		ACTION_MUTE_NAME => Ok(ActionKind::Mute),
-		ACTION_POINT_OF_INTEREST_NAME => Ok(ActionKind::PointOfInterest),
-		ACTION_FULL_VIDEO_NAME => Ok(ActionKind::FullVideo),
-		unknown_value => Err(UnknownValueError {
-			r#type: "actionType".to_owned(),
-			value: unknown_value.to_owned(),
-		}),
-	}
-}
+api_name_mappings!(
+	"actionType",
+	ActionKind => AcceptedActions,
+	convert_to_action_kind,
+	convert_from_action_kind,
+	convert_action_kind_to_bitflag,
+	ACTION_KINDS,
+	ACTION_PAIRS,
+	[
+		Skip, SKIP => "skip",
+		Mute, MUTE => "mute",
+		PointOfInterest, POINT_OF_INTEREST => "poi",
+		FullVideo, FULL_VIDEO => "full",
+	]
+);
 
 pub(crate) fn convert_category_bitflags_to_url(accepted_categories: AcceptedCategories) -> String {
-	/// Maps category values to their API names according to https://github.com/ajayyy/SponsorBlock/wiki/Types
-	const CATEGORY_PAIRS: &[(AcceptedCategories, &str)] = &[
-		(AcceptedCategories::SPONSOR, SPONSOR_NAME),
-		(
-			AcceptedCategories::UNPAID_SELF_PROMOTION,
-			UNPAID_SELF_PROMOTION_NAME,
-		),
-		(
-			AcceptedCategories::INTERACTION_REMINDER,
-			INTERACTION_REMINDER_NAME,
-		),
-		(AcceptedCategories::HIGHLIGHT, HIGHLIGHT_NAME),
-		(
-			AcceptedCategories::INTERMISSION_INTRO_ANIMATION,
-			INTERMISSION_INTRO_ANIMATION_NAME,
-		),
-		(AcceptedCategories::ENDCARDS_CREDITS, ENDCARDS_CREDITS_NAME),
-		(AcceptedCategories::PREVIEW_RECAP, PREVIEW_RECAP_NAME),
-		(AcceptedCategories::NON_MUSIC, NON_MUSIC_NAME),
-		(AcceptedCategories::FILLER_TANGENT, FILLER_TANGENT_NAME),
-		(AcceptedCategories::EXCLUSIVE_ACCESS, EXCLUSIVE_ACCESS_NAME),
-	];
-
 	to_url_array_conditional_convert(
 		CATEGORY_PAIRS,
 		|&(flag, _)| accepted_categories.contains(flag),
@@ -108,20 +121,70 @@ pub(crate) fn convert_category_bitflags_to_url(accepted_categories: AcceptedCate
 }
 
 pub(crate) fn convert_action_bitflags_to_url(accepted_actions: AcceptedActions) -> String {
-	/// Maps action types to their API names according to https://github.com/ajayyy/SponsorBlock/wiki/Types
-	const ACTION_PAIRS: &[(AcceptedActions, &str)] = &[
-		(AcceptedActions::SKIP, ACTION_SKIP_NAME),
-		(AcceptedActions::MUTE, ACTION_MUTE_NAME),
-		(
-			AcceptedActions::POINT_OF_INTEREST,
-			ACTION_POINT_OF_INTEREST_NAME,
-		),
-		(AcceptedActions::FULL_VIDEO, ACTION_FULL_VIDEO_NAME),
-	];
-
 	to_url_array_conditional_convert(
 		ACTION_PAIRS,
 		|&(flag, _)| accepted_actions.contains(flag),
 		|&(_, name)| name,
 	)
 }
+
+pub(crate) fn convert_url_to_category_bitflags(url_array: &str) -> CrateResult<AcceptedCategories> {
+	Ok(convert_url_to_bitflags(url_array, CATEGORY_PAIRS)?
+		.into_iter()
+		.fold(AcceptedCategories::NONE, |accepted, flag| accepted | flag))
+}
+
+pub(crate) fn convert_url_to_action_bitflags(url_array: &str) -> CrateResult<AcceptedActions> {
+	Ok(convert_url_to_bitflags(url_array, ACTION_PAIRS)?
+		.into_iter()
+		.fold(AcceptedActions::NONE, |accepted, flag| accepted | flag))
+}
+
+/// Parses an array of API names into the flags they correspond to, ignoring
+/// any unrecognized names.
+fn convert_url_to_bitflags<F>(url_array: &str, pairs: &[(F, &str)]) -> CrateResult<Vec<F>>
+where
+	F: Copy,
+{
+	Ok(from_json_str::<Vec<String>>(url_array)?
+		.iter()
+		.filter_map(|name| {
+			pairs
+				.iter()
+				.find(|&&(_, pair_name)| pair_name == name)
+				.map(|&(flag, _)| flag)
+		})
+		.collect())
+}
+
+#[cfg(test)]
+mod tests {
+	// Uses
+	use super::convert_action_bitflags_to_url;
+	use crate::AcceptedActions;
+
+	#[test]
+	fn point_of_interest_is_sent_as_poi() {
+		assert_eq!(
+			convert_action_bitflags_to_url(AcceptedActions::POINT_OF_INTEREST),
+			r#"["poi"]"#
+		);
+		assert_eq!(AcceptedActions::points().to_api_string(), r#"["poi"]"#);
+	}
+
+	#[cfg(feature = "user")]
+	#[test]
+	fn point_of_interest_is_in_the_segments_url() {
+		let url = crate::Client::new("test")
+			.build_segments_url(
+				"dQw4w9WgXcQ",
+				crate::AcceptedCategories::all(),
+				AcceptedActions::POINT_OF_INTEREST,
+			)
+			.unwrap();
+		assert!(
+			url.contains("actionTypes=%5B%22poi%22%5D"),
+			"unexpected URL: {url}"
+		);
+	}
+}
@@ -0,0 +1,214 @@
+//! Caching of responses using conditional requests.
+
+// Uses
+use std::{
+	collections::HashMap,
+	sync::{Arc, Mutex},
+};
+
+use reqwest::{
+	header::{HeaderMap, HeaderName, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+	Request,
+	Response,
+	StatusCode,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{error::Result, util::get_response_text};
+
+/// A single cached response for an endpoint, revalidated with the server using
+/// conditional requests.
+///
+/// The cache is only filled if the server provides an `ETag` or
+/// `Last-Modified` header.
+#[derive(Debug, Default)]
+pub(crate) struct ResponseCache {
+	entry: Mutex<Option<CachedResponse>>,
+	/// Whether the cache belongs to a single request, rather than being shared
+	/// by every identical request.
+	private: bool,
+}
+
+/// A set of [`ResponseCache`]s, one for each distinct request URL.
+///
+/// Entries are never removed, so this grows with the number of distinct
+/// requests made.
+#[derive(Debug, Default)]
+pub(crate) struct ResponseCacheMap {
+	caches: Mutex<HashMap<String, Arc<ResponseCache>>>,
+}
+
+/// A cached response and the validators needed to check it's still current.
+///
+/// The text is [`None`] when the cache was seeded with validators for a
+/// response held elsewhere.
+#[derive(Debug)]
+struct CachedResponse {
+	validator: CacheValidator,
+	text: Option<String>,
+}
+
+/// The headers that identify a version of a response, for asking the server
+/// whether it has changed since.
+///
+/// This is for callers that keep their own cache of responses. See
+/// [`Client::fetch_segments_versioned`] for more information.
+///
+/// [`Client::fetch_segments_versioned`]: crate::Client::fetch_segments_versioned
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct CacheValidator {
+	/// The value of the `ETag` header of the response.
+	pub etag: Option<String>,
+	/// The value of the `Last-Modified` header of the response.
+	pub last_modified: Option<String>,
+}
+
+impl CacheValidator {
+	/// Creates a new validator, such as for restoring one that was saved.
+	#[must_use]
+	pub fn new(etag: Option<String>, last_modified: Option<String>) -> Self {
+		Self {
+			etag,
+			last_modified,
+		}
+	}
+
+	/// Whether the validator can't be used to check for changes, because the
+	/// server didn't provide either header.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.etag.is_none() && self.last_modified.is_none()
+	}
+
+	/// Gets the validator from the headers of a response.
+	fn from_headers(headers: &HeaderMap) -> Self {
+		Self {
+			etag: header_string(headers, &ETAG),
+			last_modified: header_string(headers, &LAST_MODIFIED),
+		}
+	}
+}
+
+impl ResponseCache {
+	/// Creates a cache that revalidates a response held elsewhere, which was
+	/// received with `validator`.
+	///
+	/// If the server says the response hasn't changed, [`get_response_text`]
+	/// returns an empty string, since there's no cached text to return.
+	///
+	/// The cache is private to the request it's used for, since the caller
+	/// needs the validator it ends up with.
+	///
+	/// [`get_response_text`]: Self::get_response_text
+	pub(crate) fn seeded(validator: CacheValidator) -> Self {
+		Self {
+			entry: Mutex::new(Some(CachedResponse {
+				validator,
+				text: None,
+			})),
+			private: true,
+		}
+	}
+
+	/// Whether the cache is shared by every identical request, so the response
+	/// to one of them is just as good for the others.
+	pub(crate) fn is_shared(&self) -> bool {
+		!self.private
+	}
+
+	/// Gets the validator for the latest response.
+	///
+	/// The validator is empty if the server didn't provide one.
+	pub(crate) fn validator(&self) -> CacheValidator {
+		self.entry
+			.lock()
+			.expect("the response cache lock was poisoned")
+			.as_ref()
+			.map(|cached| cached.validator.clone())
+			.unwrap_or_default()
+	}
+
+	/// Adds the conditional request headers for the cached response to a
+	/// request, if there is one.
+	pub(crate) fn add_validators(&self, request: &mut Request) {
+		let entry = self
+			.entry
+			.lock()
+			.expect("the response cache lock was poisoned");
+		let Some(cached) = entry.as_ref() else {
+			return;
+		};
+
+		let headers = request.headers_mut();
+		if let Some(etag) = cached.validator.etag.as_ref().and_then(|v| v.parse().ok()) {
+			headers.insert(IF_NONE_MATCH, etag);
+		}
+		if let Some(last_modified) = cached
+			.validator
+			.last_modified
+			.as_ref()
+			.and_then(|v| v.parse().ok())
+		{
+			headers.insert(IF_MODIFIED_SINCE, last_modified);
+		}
+	}
+
+	/// Gets the text of a response, using the cached text if the server says it
+	/// hasn't changed and caching the new text otherwise.
+	pub(crate) async fn get_response_text(
+		&self,
+		response: Response,
+		max_bytes: Option<usize>,
+	) -> Result<String> {
+		if response.status() == StatusCode::NOT_MODIFIED {
+			let entry = self
+				.entry
+				.lock()
+				.expect("the response cache lock was poisoned");
+			if let Some(cached) = entry.as_ref() {
+				return Ok(cached.text.clone().unwrap_or_default());
+			}
+		}
+
+		let validator = CacheValidator::from_headers(response.headers());
+		let text = get_response_text(response, max_bytes).await?;
+
+		let mut entry = self
+			.entry
+			.lock()
+			.expect("the response cache lock was poisoned");
+		*entry = (!validator.is_empty()).then(|| CachedResponse {
+			validator,
+			text: Some(text.clone()),
+		});
+
+		Ok(text)
+	}
+}
+
+impl ResponseCacheMap {
+	/// Gets the cache for a request URL, creating an empty one if there isn't
+	/// one yet.
+	pub(crate) fn get(&self, url: &str) -> Arc<ResponseCache> {
+		let mut caches = self
+			.caches
+			.lock()
+			.expect("the response cache lock was poisoned");
+		if let Some(cache) = caches.get(url) {
+			return Arc::clone(cache);
+		}
+
+		let cache = Arc::<ResponseCache>::default();
+		caches.insert(url.to_owned(), Arc::clone(&cache));
+		cache
+	}
+}
+
+/// Gets the value of a header as a string, if it's present and valid.
+fn header_string(headers: &HeaderMap, name: &HeaderName) -> Option<String> {
+	headers
+		.get(name)
+		.and_then(|value| value.to_str().ok())
+		.map(ToOwned::to_owned)
+}
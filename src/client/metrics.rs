@@ -0,0 +1,64 @@
+//! Hooks for collecting metrics about requests to the API.
+
+// Uses
+use time::Duration;
+
+use crate::error::{SponsorBlockError, StatusCode};
+
+/// A receiver for metrics about the requests the client sends.
+///
+/// Implement this to forward request counts, latencies, and outcomes to
+/// whichever metrics system you use, then install it with
+/// [`ClientBuilder::metrics`].
+///
+/// [`ClientBuilder::metrics`]: crate::ClientBuilder::metrics
+pub trait Metrics: Send + Sync {
+	/// Records a single completed request to the API.
+	///
+	/// `endpoint` is the API endpoint the request was sent to, without the
+	/// base URL, any path parameters, or the query string (for example,
+	/// `/skipSegments`). `duration` covers sending the request and receiving
+	/// the full response, but not any time spent waiting on the client's rate
+	/// limit.
+	///
+	/// This is called on the task that sent the request, so it should return
+	/// quickly.
+	fn record_request(&self, endpoint: &str, duration: Duration, outcome: RequestOutcome);
+}
+
+/// The outcome of a request to the API, as reported to [`Metrics`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum RequestOutcome {
+	/// The request succeeded.
+	Success,
+	/// The server responded with a server error status code.
+	///
+	/// Contains the status code returned by the server.
+	ServerError(StatusCode),
+	/// The server responded with a client error status code. This includes
+	/// 404s for videos that aren't in the database.
+	///
+	/// Contains the status code returned by the server.
+	ClientError(StatusCode),
+	/// The server responded with an unexpected status code.
+	///
+	/// Contains the status code returned by the server.
+	UnknownStatus(StatusCode),
+	/// The request couldn't be completed, likely due to a network issue or a
+	/// timeout.
+	CommunicationError,
+}
+
+impl RequestOutcome {
+	/// Classifies the result of sending a request.
+	pub(crate) fn from_result<T>(result: &Result<T, SponsorBlockError>) -> Self {
+		match result {
+			Ok(_) => Self::Success,
+			Err(SponsorBlockError::HttpApi(status)) => Self::ServerError(*status),
+			Err(SponsorBlockError::HttpClient(status)) => Self::ClientError(*status),
+			Err(SponsorBlockError::HttpUnknown(status)) => Self::UnknownStatus(*status),
+			Err(_) => Self::CommunicationError,
+		}
+	}
+}
@@ -1,32 +1,97 @@
 //! The SponsorBlock client.
 
+// Uses
+#[cfg(feature = "metrics")]
+use std::time::Instant;
+use std::{
+	sync::{Arc, Mutex, PoisonError},
+	time::Duration as StdDuration,
+};
+
+#[cfg(feature = "mock")]
+use http::Response as HttpResponse;
+use reqwest::{
+	header::{HeaderValue, USER_AGENT},
+	Client as ReqwestClient,
+	ClientBuilder as ReqwestClientBuilder,
+	Request,
+	RequestBuilder,
+	Url,
+};
+#[cfg(feature = "mock")]
+use reqwest::{Response, StatusCode as HttpStatusCode};
+use time::Duration;
+
+use self::{
+	cache::{ResponseCache, ResponseCacheMap},
+	rate_limit::RateLimiter,
+	single_flight::{Role, SingleFlight},
+};
+#[cfg(feature = "mock")]
+use crate::error::StatusCode;
+use crate::{
+	error::{Result, SponsorBlockError},
+	util::get_response_text,
+};
+
 // Modules
+mod cache;
+#[cfg(feature = "metrics")]
+mod metrics;
+mod rate_limit;
+mod single_flight;
 #[cfg(feature = "user")]
 mod user;
 #[cfg(feature = "vip")]
 mod vip;
 
-// Uses
-use reqwest::{Client as ReqwestClient, ClientBuilder as ReqwestClientBuilder};
-use time::Duration;
-
 // Public Exports
+#[cfg(feature = "metrics")]
+pub use self::metrics::*;
 #[cfg(feature = "user")]
 pub use self::user::*;
 #[cfg(feature = "vip")]
 pub use self::vip::*;
+pub use self::{cache::CacheValidator, rate_limit::RateLimitStatus};
+
+/// A function that returns a canned status code and response text for a
+/// request URL, used in place of actually sending requests.
+///
+/// See [`ClientBuilder::responder`] for more information.
+#[cfg(feature = "mock")]
+pub type Responder = Arc<dyn Fn(&str) -> (StatusCode, String) + Send + Sync>;
 
 /// The client for interfacing with SponsorBlock.
+///
+/// Cloning the client is cheap, and clones share the same connection pool and
+/// rate limit.
+#[derive(Clone)]
 pub struct Client {
 	// Internal
 	http: ReqwestClient,
+	#[cfg(feature = "mock")]
+	responder: Option<Responder>,
+	rate_limiter: Option<Arc<RateLimiter>>,
+	#[cfg(feature = "metrics")]
+	metrics: Option<Arc<dyn Metrics>>,
+	status_cache: Arc<ResponseCache>,
+	segment_caches: Option<Arc<ResponseCacheMap>>,
+	single_flight: Option<Arc<SingleFlight>>,
+	rate_limit_status: Arc<Mutex<Option<RateLimitStatus>>>,
+	user_agent: Option<HeaderValue>,
 
 	// Config
 	user_id: String,
-	base_url: String,
+	api_url: String,
 	#[cfg(feature = "private_searches")]
 	hash_prefix_length: u8,
 	service: String,
+	segment_timeout: Option<StdDuration>,
+	status_timeout: Option<StdDuration>,
+	skip_unknown_segments: bool,
+	rewrite_highlight_action: bool,
+	auto_parse_video_ids: bool,
+	max_response_bytes: Option<usize>,
 }
 
 impl Client {
@@ -47,21 +112,351 @@ impl Client {
 	{
 		ClientBuilder::new(user_id)
 	}
+
+	/// Creates a new instance of the client that never contacts the API,
+	/// instead answering every request with the provided responder.
+	///
+	/// See [`ClientBuilder::responder`] for more information.
+	#[cfg(feature = "mock")]
+	#[must_use]
+	pub fn with_responder<U, R>(user_id: U, responder: R) -> Self
+	where
+		U: Into<String>,
+		R: Fn(&str) -> (StatusCode, String) + Send + Sync + 'static,
+	{
+		ClientBuilder::new(user_id).responder(responder).build()
+	}
+
+	/// Creates a copy of the client that sends a different user agent with its
+	/// requests.
+	///
+	/// This is useful when making requests on behalf of several different
+	/// applications, since the copy shares everything else with the original,
+	/// including the connection pool and rate limit. Creating a copy is cheap,
+	/// so it's fine to do for each request.
+	///
+	/// # Errors
+	/// Returns [`BadData`] if the user agent isn't a valid HTTP header value,
+	/// such as if it contains a newline.
+	///
+	/// [`BadData`]: SponsorBlockError::BadData
+	pub fn with_user_agent<A>(&self, user_agent: A) -> Result<Self>
+	where
+		A: AsRef<str>,
+	{
+		let user_agent = user_agent.as_ref();
+		let user_agent = HeaderValue::from_str(user_agent).map_err(|_| {
+			SponsorBlockError::BadData(format!("{user_agent:?} is not a valid HTTP header value"))
+		})?;
+		Ok(Self {
+			user_agent: Some(user_agent),
+			..self.clone()
+		})
+	}
+
+	/// Gets the absolute URLs of every API endpoint the client may contact,
+	/// such as for allowlisting them.
+	///
+	/// The URLs are based on the configured base URL and API path, and only
+	/// include the endpoints used by the enabled features. Some requests add
+	/// to the path - under the `private_searches` feature, segment requests
+	/// append the hash prefix to the `skipSegments` endpoint. Query strings
+	/// aren't included.
+	#[must_use]
+	pub fn endpoints(&self) -> Vec<String> {
+		#[cfg(feature = "user")]
+		let endpoints = user::ENDPOINTS;
+		#[cfg(not(feature = "user"))]
+		let endpoints: &[&str] = &[];
+
+		endpoints
+			.iter()
+			.map(|endpoint| format!("{}{}", self.api_url, endpoint))
+			.collect()
+	}
+
+	/// Gets the rate limit quota reported by the server with the latest
+	/// response, for throttling requests before hitting the limit.
+	///
+	/// This is updated after every request, and is shared between clones of
+	/// the client. It's [`None`] if no requests have been sent yet, or if the
+	/// latest response didn't include any `X-RateLimit-*` headers.
+	#[must_use]
+	pub fn rate_limit_status(&self) -> Option<RateLimitStatus> {
+		*self
+			.rate_limit_status
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+	}
+
+	/// Applies an operation-specific timeout to a request, if one is set.
+	///
+	/// Requests without one use the timeout of the underlying HTTP client.
+	pub(crate) fn with_timeout(
+		request: RequestBuilder,
+		timeout: Option<StdDuration>,
+	) -> RequestBuilder {
+		match timeout {
+			Some(timeout) => request.timeout(timeout),
+			None => request,
+		}
+	}
+
+	/// Sends a request to the API and returns the response text.
+	///
+	/// All requests made by the client go through here.
+	pub(crate) async fn send_request(&self, request: RequestBuilder) -> Result<String> {
+		self.send_cached_request(request, None).await
+	}
+
+	/// Sends a request to the API and returns the response text, revalidating
+	/// the response held in `cache` if there is one.
+	///
+	/// See [`send_request`] for more information.
+	///
+	/// [`send_request`]: Self::send_request
+	pub(crate) async fn send_cached_request(
+		&self,
+		request: RequestBuilder,
+		cache: Option<&ResponseCache>,
+	) -> Result<String> {
+		let mut request = request.build()?;
+
+		if let Some(user_agent) = &self.user_agent {
+			request.headers_mut().insert(USER_AGENT, user_agent.clone());
+		}
+		if let Some(cache) = cache {
+			cache.add_validators(&mut request);
+		}
+
+		// Responses can't be shared when revalidating a private cache, since the
+		// request's own cache has to be updated with the response
+		let shareable = cache.is_none_or(ResponseCache::is_shared);
+		if let (Some(single_flight), true) = (&self.single_flight, shareable) {
+			let key = format!("{} {:?}", request.url(), request.headers().get(USER_AGENT));
+			match single_flight.join(key) {
+				Role::Leader(guard) => {
+					let result = self.send_built_request(request, cache).await;
+					guard.finish(result.as_ref().ok().cloned());
+					return result;
+				}
+				Role::Follower(follower) => {
+					if let Some(text) = follower.wait().await {
+						return Ok(text);
+					}
+				}
+			}
+		}
+
+		self.send_built_request(request, cache).await
+	}
+
+	/// Sends a request that's ready to go, respecting the rate limit and
+	/// recording metrics.
+	async fn send_built_request(
+		&self,
+		request: Request,
+		cache: Option<&ResponseCache>,
+	) -> Result<String> {
+		if let Some(rate_limiter) = &self.rate_limiter {
+			rate_limiter.acquire().await;
+		}
+
+		#[cfg(feature = "metrics")]
+		if let Some(metrics) = &self.metrics {
+			let endpoint = self.endpoint_of(request.url().path()).to_owned();
+
+			let start = Instant::now();
+			let result = self.execute_request(request, cache).await;
+			metrics.record_request(
+				endpoint.as_str(),
+				start.elapsed().try_into().unwrap_or(Duration::MAX),
+				RequestOutcome::from_result(&result),
+			);
+
+			return result;
+		}
+
+		self.execute_request(request, cache).await
+	}
+
+	/// Actually sends a request, without any of the extra handling from
+	/// [`send_cached_request`].
+	///
+	/// [`send_cached_request`]: Self::send_cached_request
+	async fn execute_request(
+		&self,
+		request: Request,
+		cache: Option<&ResponseCache>,
+	) -> Result<String> {
+		#[cfg(feature = "mock")]
+		let response = match &self.responder {
+			Some(responder) => {
+				let (status, text) = responder(request.url().as_str());
+				let mut response = HttpResponse::new(text);
+				*response.status_mut() = HttpStatusCode::from_u16(status)
+					.map_err(|_| SponsorBlockError::HttpUnknown(status))?;
+				Response::from(response)
+			}
+			None => self.http.execute(request).await?,
+		};
+		#[cfg(not(feature = "mock"))]
+		let response = self.http.execute(request).await?;
+		*self
+			.rate_limit_status
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner) = RateLimitStatus::from_headers(response.headers());
+		match cache {
+			Some(cache) => {
+				cache
+					.get_response_text(response, self.max_response_bytes)
+					.await
+			}
+			None => get_response_text(response, self.max_response_bytes).await,
+		}
+	}
+
+	/// Extracts the endpoint from the path of a request URL, leaving out the
+	/// API path and any path parameters.
+	#[cfg(feature = "metrics")]
+	fn endpoint_of<'a>(&self, url_path: &'a str) -> &'a str {
+		let api_path = self
+			.api_url
+			.split_once("://")
+			.and_then(|(_, rest)| rest.find('/').map(|index| &rest[index..]))
+			.unwrap_or_default();
+		let endpoint = url_path.strip_prefix(api_path).unwrap_or(url_path);
+		match endpoint.get(1..).and_then(|rest| rest.find('/')) {
+			Some(index) => &endpoint[..=index],
+			None => endpoint,
+		}
+	}
+}
+
+/// How much privacy to trade for bandwidth when searching for segments.
+///
+/// Segment searches only send the start of a hash of the video ID to the API,
+/// which responds with the segments for every video whose hash starts the
+/// same way. Sending less of the hash makes it harder to tell which video was
+/// actually being searched for, but means more unrelated videos are sent back.
+///
+/// See [`ClientBuilder::privacy_level`] for more information.
+#[cfg(feature = "private_searches")]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum PrivacyLevel {
+	/// The most privacy, at the cost of the most bandwidth. This is what the
+	/// official browser extension uses.
+	///
+	/// Uses a hash prefix length of 4.
+	#[default]
+	Maximum,
+	/// A middle ground between privacy and bandwidth.
+	///
+	/// Uses a hash prefix length of 6.
+	Balanced,
+	/// The least bandwidth, at the cost of making the searched video much
+	/// easier to identify.
+	///
+	/// Uses a hash prefix length of 10.
+	Minimal,
+}
+
+#[cfg(feature = "private_searches")]
+impl PrivacyLevel {
+	/// Gets the hash prefix length that the privacy level corresponds to.
+	#[must_use]
+	pub const fn hash_prefix_length(self) -> u8 {
+		match self {
+			Self::Maximum => 4,
+			Self::Balanced => 6,
+			Self::Minimal => 10,
+		}
+	}
+}
+
+/// Estimates how many videos share a hash prefix of the given length, out of a
+/// database of `video_count` videos.
+///
+/// This is the size of the anonymity set for a private search: the searched
+/// video plus the number of other videos expected to share its prefix,
+/// rounded to the nearest whole video. It's always at least `1`. Each
+/// character of the prefix is a hexadecimal digit, so every extra character
+/// divides the number of other matching videos by 16.
+///
+/// The API doesn't report how many videos have segments, but
+/// [`TotalStats::total_submissions`] can be used as an upper bound, since
+/// every video with segments has at least one.
+///
+/// See [`ClientBuilder::hash_prefix_length`] for more information.
+#[cfg(feature = "private_searches")]
+#[must_use]
+pub fn estimate_prefix_collisions(hash_prefix_length: u8, video_count: u64) -> u64 {
+	let prefix_count = 16_f64.powi(i32::from(hash_prefix_length));
+	let others = video_count.saturating_sub(1) as f64 / prefix_count;
+	(others.round() as u64).saturating_add(1)
+}
+
+/// A known instance of the SponsorBlock API.
+///
+/// See [`ClientBuilder::mirror`] for more information.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum Mirror {
+	/// The official API. See [`ClientBuilder::BASE_URL_MAIN`].
+	#[default]
+	Main,
+	/// The official testing database. See [`ClientBuilder::BASE_URL_TESTING`].
+	Testing,
+	/// The community mirror run by the Piped project. See
+	/// [`ClientBuilder::BASE_URL_MIRROR_KAVIN`].
+	///
+	/// Mirrors may lag behind the official database, and may not support
+	/// every endpoint.
+	Kavin,
+}
+
+impl Mirror {
+	/// Gets the base URL of the instance.
+	#[must_use]
+	pub const fn base_url(self) -> &'static str {
+		match self {
+			Self::Main => ClientBuilder::BASE_URL_MAIN,
+			Self::Testing => ClientBuilder::BASE_URL_TESTING,
+			Self::Kavin => ClientBuilder::BASE_URL_MIRROR_KAVIN,
+		}
+	}
 }
 
 /// The builder for the [`Client`].
 #[derive(Clone)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct ClientBuilder {
 	// Internal
 	user_agent: String,
+	#[cfg(feature = "mock")]
+	responder: Option<Responder>,
+	#[cfg(feature = "metrics")]
+	metrics: Option<Arc<dyn Metrics>>,
 
 	// Config
 	user_id: String,
 	base_url: String,
+	api_path: String,
 	#[cfg(feature = "private_searches")]
 	hash_prefix_length: u8,
 	service: String,
 	timeout: Option<Duration>,
+	segment_timeout: Option<Duration>,
+	status_timeout: Option<Duration>,
+	rate_limit: Option<(u32, Duration)>,
+	skip_unknown_segments: bool,
+	rewrite_highlight_action: bool,
+	auto_parse_video_ids: bool,
+	max_response_bytes: Option<usize>,
+	single_flight: bool,
+	conditional_segment_requests: bool,
+	danger_accept_invalid_certs: bool,
 }
 
 impl ClientBuilder {
@@ -72,13 +467,26 @@ impl ClientBuilder {
 	/// See [`base_url`] for more information.
 	///
 	/// [`base_url`]: Self::base_url
-	pub const BASE_URL_MAIN: &'static str = "https://sponsor.ajay.app/api";
+	pub const BASE_URL_MAIN: &'static str = "https://sponsor.ajay.app";
 	/// The base URL for the SponsorBlock testing database.
 	///
 	/// See [`base_url`] for more information.
 	///
 	/// [`base_url`]: Self::base_url
-	pub const BASE_URL_TESTING: &'static str = "https://sponsor.ajay.app/test/api";
+	pub const BASE_URL_TESTING: &'static str = "https://sponsor.ajay.app/test";
+	/// The base URL for the community mirror of the SponsorBlock database run
+	/// by the Piped project.
+	///
+	/// See [`base_url`] for more information.
+	///
+	/// [`base_url`]: Self::base_url
+	pub const BASE_URL_MIRROR_KAVIN: &'static str = "https://sponsorblock.kavin.rocks";
+	/// The default API path, used by official instances.
+	///
+	/// See [`api_path`] for more information.
+	///
+	/// [`api_path`]: Self::api_path
+	pub const DEFAULT_API_PATH: &'static str = "/api";
 	/// The default hash prefix length.
 	///
 	/// See [`hash_prefix_length`] for more information.
@@ -111,56 +519,229 @@ impl ClientBuilder {
 	{
 		Self {
 			user_agent: Self::DEFAULT_USER_AGENT.to_owned(),
+			#[cfg(feature = "mock")]
+			responder: None,
+			#[cfg(feature = "metrics")]
+			metrics: None,
 			user_id: user_id.into(),
 			base_url: Self::BASE_URL_MAIN.to_owned(),
+			api_path: Self::DEFAULT_API_PATH.to_owned(),
 			#[cfg(feature = "private_searches")]
 			hash_prefix_length: Self::DEFAULT_HASH_PREFIX_LENGTH,
 			service: Self::DEFAULT_SERVICE.to_owned(),
 			timeout: Some(Self::DEFAULT_TIMEOUT),
+			segment_timeout: None,
+			status_timeout: None,
+			rate_limit: None,
+			skip_unknown_segments: false,
+			rewrite_highlight_action: true,
+			auto_parse_video_ids: false,
+			max_response_bytes: None,
+			single_flight: false,
+			conditional_segment_requests: false,
+			danger_accept_invalid_certs: false,
 		}
 	}
 
 	/// Builds the struct into an instance of [`Client`].
 	///
 	/// # Panics
+	/// - If the [`base_url`] isn't a valid HTTP or HTTPS URL. See [`try_build`]
+	///   for a version that returns an error instead.
 	/// - If the underlying HTTP client fails to build for some reason.
-	/// - If the timeout is of a value that is incompatible with the std
-	///   library.
+	/// - If any of the timeouts or the rate limit period are of a value that is
+	///   incompatible with the std library.
 	///
-	/// If either happens, please open an issue.
+	/// If either of the last two happens, please open an issue.
+	///
+	/// [`base_url`]: Self::base_url
+	/// [`try_build`]: Self::try_build
 	#[must_use]
 	pub fn build(&self) -> Client {
-		let mut http = ReqwestClientBuilder::new().user_agent(self.user_agent.clone());
-		if let Some(timeout) = self.timeout {
-			http = http.timeout(timeout.try_into().expect(
+		self.try_build().expect("unable to build the client")
+	}
+
+	/// Builds the struct into an instance of [`Client`], returning an error
+	/// instead of panicking if the configuration is invalid.
+	///
+	/// This is useful for catching configuration mistakes, such as a
+	/// [`base_url`] without a scheme, when the client is built rather than as
+	/// failed requests later on. Repeated slashes in the path of the API URL
+	/// are collapsed, and a [`base_url`] that ends with the [`api_path`] has it
+	/// removed, since older versions of the library expected it there.
+	///
+	/// # Errors
+	/// - Returns [`BadData`] if the [`base_url`] isn't a valid HTTP or HTTPS
+	///   URL.
+	/// - Returns [`HttpCommunication`] if the underlying HTTP client fails to
+	///   build.
+	///
+	/// # Panics
+	/// If any of the timeouts or the rate limit period are of a value that is
+	/// incompatible with the std library. If this happens, please open an
+	/// issue.
+	///
+	/// [`base_url`]: Self::base_url
+	/// [`api_path`]: Self::api_path
+	/// [`BadData`]: SponsorBlockError::BadData
+	/// [`HttpCommunication`]: SponsorBlockError::HttpCommunication
+	pub fn try_build(&self) -> Result<Client> {
+		let api_url = self.api_url()?;
+		let to_std_timeout = |timeout: Duration| -> StdDuration {
+			timeout.try_into().expect(
 				"the Duration value provided for the HTTP timeout is incompatible with the std \
 				 library implementation",
-			));
+			)
+		};
+
+		let mut http = ReqwestClientBuilder::new()
+			.user_agent(self.user_agent.clone())
+			.danger_accept_invalid_certs(self.danger_accept_invalid_certs);
+		if let Some(timeout) = self.timeout {
+			http = http.timeout(to_std_timeout(timeout));
 		}
-		Client {
-			http: http.build().expect("unable to build the HTTP client"),
+		Ok(Client {
+			http: http.build()?,
+			#[cfg(feature = "mock")]
+			responder: self.responder.clone(),
+			rate_limiter: self.rate_limit.map(|(max_per, per)| {
+				Arc::new(RateLimiter::new(
+					max_per,
+					per.try_into().expect(
+						"the Duration value provided for the rate limit period is incompatible \
+						 with the std library implementation",
+					),
+				))
+			}),
+			#[cfg(feature = "metrics")]
+			metrics: self.metrics.clone(),
+			status_cache: Arc::default(),
+			segment_caches: self.conditional_segment_requests.then(Arc::default),
+			single_flight: self.single_flight.then(Arc::default),
+			rate_limit_status: Arc::default(),
+			user_agent: None,
 			user_id: self.user_id.clone(),
-			base_url: self.base_url.clone(),
+			api_url,
 			#[cfg(feature = "private_searches")]
 			hash_prefix_length: self.hash_prefix_length,
 			service: self.service.clone(),
+			segment_timeout: self.segment_timeout.map(to_std_timeout),
+			status_timeout: self.status_timeout.map(to_std_timeout),
+			skip_unknown_segments: self.skip_unknown_segments,
+			rewrite_highlight_action: self.rewrite_highlight_action,
+			auto_parse_video_ids: self.auto_parse_video_ids,
+			max_response_bytes: self.max_response_bytes,
+		})
+	}
+
+	/// Joins the base URL and API path into the full API URL, validating it
+	/// along the way.
+	fn api_url(&self) -> Result<String> {
+		let invalid = |reason: &str| {
+			SponsorBlockError::BadData(format!(
+				"the base URL {:?} is invalid: {reason}",
+				self.base_url
+			))
+		};
+
+		let mut url = Url::parse(&self.base_url).map_err(|e| invalid(&e.to_string()))?;
+		if !matches!(url.scheme(), "http" | "https") || url.cannot_be_a_base() {
+			return Err(invalid("it must be an HTTP or HTTPS URL"));
+		}
+
+		// Base URLs used to include the API path, so strip it rather than
+		// requesting it twice
+		let base_path = url.path().trim_end_matches('/');
+		let base_path = if self.api_path.is_empty() {
+			base_path
+		} else {
+			base_path
+				.strip_suffix(self.api_path.as_str())
+				.unwrap_or(base_path)
+		};
+
+		let mut path = String::with_capacity(base_path.len() + self.api_path.len());
+		for c in base_path.chars().chain(self.api_path.chars()) {
+			if !(c == '/' && path.ends_with('/')) {
+				path.push(c);
+			}
 		}
+		url.set_path(path.as_str());
+
+		Ok(url.as_str().trim_end_matches('/').to_owned())
+	}
+
+	/// Sets the local user ID to use with the API.
+	///
+	/// This replaces the one provided when creating the builder, which is
+	/// useful if it isn't known until later on.
+	pub fn user_id<U>(&mut self, user_id: U) -> &mut Self
+	where
+		U: Into<String>,
+	{
+		self.user_id = user_id.into();
+		self
 	}
 
-	/// Sets the base URL to access for the API. This includes the `/api` in
-	/// official instances.
+	/// Sets the base URL to access for the API. This *does not* include the
+	/// `/api` in official instances - see [`api_path`] for that.
 	///
 	/// You should only have to change this if working with a different instance
 	/// of the SponsorBlock database.
 	///
+	/// Older versions of the library expected the base URL to include the API
+	/// path, like `https://sponsor.ajay.app/api`. For compatibility, if the
+	/// base URL ends with the [`api_path`], it's removed when the client is
+	/// built so the API path isn't requested twice. An instance that really is
+	/// served from a doubled path can be reached by including it all in the
+	/// base URL and setting the [`api_path`] to an empty string.
+	///
 	/// The default value is [`BASE_URL_MAIN`].
 	///
+	/// [`api_path`]: Self::api_path
 	/// [`BASE_URL_MAIN`]: Self::BASE_URL_MAIN
 	pub fn base_url<U>(&mut self, base_url: U) -> &mut Self
 	where
 		U: AsRef<str>,
 	{
-		self.base_url = base_url.as_ref().trim_end_matches('/').to_owned();
+		base_url
+			.as_ref()
+			.trim_end_matches('/')
+			.clone_into(&mut self.base_url);
+		self
+	}
+
+	/// Sets the base URL to that of a known instance of the API.
+	///
+	/// This is the same as calling [`base_url`] with [`Mirror::base_url`], and
+	/// doesn't change the [`api_path`].
+	///
+	/// [`base_url`]: Self::base_url
+	/// [`api_path`]: Self::api_path
+	pub fn mirror(&mut self, mirror: Mirror) -> &mut Self {
+		self.base_url(mirror.base_url())
+	}
+
+	/// Sets the path the API is mounted at, relative to the [`base_url`].
+	///
+	/// You should only have to change this if working with an instance that
+	/// serves the API from a different path, such as one behind a reverse
+	/// proxy.
+	///
+	/// The default value is [`DEFAULT_API_PATH`].
+	///
+	/// [`base_url`]: Self::base_url
+	/// [`DEFAULT_API_PATH`]: Self::DEFAULT_API_PATH
+	pub fn api_path<P>(&mut self, api_path: P) -> &mut Self
+	where
+		P: AsRef<str>,
+	{
+		let api_path = api_path.as_ref().trim_matches('/');
+		self.api_path = if api_path.is_empty() {
+			String::new()
+		} else {
+			format!("/{api_path}")
+		};
 		self
 	}
 
@@ -168,10 +749,14 @@ impl ClientBuilder {
 	///
 	/// This is the number of characters of the hash sent to the server. Smaller
 	/// values will in theory mean more potential matches will have to be sent
-	/// by the API, but will provide more privacy.
+	/// by the API, but will provide more privacy. See
+	/// [`estimate_prefix_collisions`] for putting a number on the tradeoff.
 	///
 	/// # Panics
-	/// Panics if not in the range `4 <= hash_prefix_length <= 32`.
+	/// Panics if not in the range `4 <= hash_prefix_length <= 32`. See
+	/// [`try_hash_prefix_length`] for a non-panicking version.
+	///
+	/// [`try_hash_prefix_length`]: Self::try_hash_prefix_length
 	#[cfg(feature = "private_searches")]
 	pub fn hash_prefix_length(&mut self, hash_prefix_length: u8) -> &mut Self {
 		assert!(hash_prefix_length >= 4);
@@ -180,6 +765,41 @@ impl ClientBuilder {
 		self
 	}
 
+	/// Sets the hash prefix length to use for private searches, based on a
+	/// [`PrivacyLevel`].
+	///
+	/// This is a friendlier alternative to [`hash_prefix_length`].
+	///
+	/// [`hash_prefix_length`]: Self::hash_prefix_length
+	#[cfg(feature = "private_searches")]
+	pub fn privacy_level(&mut self, privacy_level: PrivacyLevel) -> &mut Self {
+		self.hash_prefix_length = privacy_level.hash_prefix_length();
+		self
+	}
+
+	/// Sets the hash prefix length to use for private searches, returning an
+	/// error instead of panicking if it's invalid.
+	///
+	/// This is useful for values that come from user configuration. See
+	/// [`hash_prefix_length`] for more information.
+	///
+	/// # Errors
+	/// Returns [`InvalidHashPrefixLength`] if not in the range
+	/// `4 <= hash_prefix_length <= 32`.
+	///
+	/// [`hash_prefix_length`]: Self::hash_prefix_length
+	/// [`InvalidHashPrefixLength`]: SponsorBlockError::InvalidHashPrefixLength
+	#[cfg(feature = "private_searches")]
+	pub fn try_hash_prefix_length(&mut self, hash_prefix_length: u8) -> Result<&mut Self> {
+		if !(4..=32).contains(&hash_prefix_length) {
+			return Err(SponsorBlockError::InvalidHashPrefixLength(
+				hash_prefix_length,
+			));
+		}
+		self.hash_prefix_length = hash_prefix_length;
+		Ok(self)
+	}
+
 	/// Sets the service value to use with the API.
 	///
 	/// See <https://wiki.sponsor.ajay.app/w/Types#Service> for more information.
@@ -222,4 +842,286 @@ impl ClientBuilder {
 		self.timeout(millis.map(Duration::milliseconds));
 		self
 	}
+
+	/// Sets the HTTP request timeout for segment requests, overriding the
+	/// global [`timeout`].
+	///
+	/// This applies to fetching segments and segment info.
+	///
+	/// The default is [`None`], which falls back to the global [`timeout`].
+	///
+	/// # Panics
+	/// Panics if not in the range `duration > 0`.
+	///
+	/// [`timeout`]: Self::timeout
+	pub fn segment_timeout(&mut self, duration: Option<Duration>) -> &mut Self {
+		if let Some(duration_value) = duration {
+			assert!(duration_value.is_positive());
+		}
+
+		self.segment_timeout = duration;
+		self
+	}
+
+	/// Sets the HTTP request timeout for API status requests, overriding the
+	/// global [`timeout`].
+	///
+	/// This is useful for health checks, which should usually fail fast.
+	///
+	/// The default is [`None`], which falls back to the global [`timeout`].
+	///
+	/// # Panics
+	/// Panics if not in the range `duration > 0`.
+	///
+	/// [`timeout`]: Self::timeout
+	pub fn status_timeout(&mut self, duration: Option<Duration>) -> &mut Self {
+		if let Some(duration_value) = duration {
+			assert!(duration_value.is_positive());
+		}
+
+		self.status_timeout = duration;
+		self
+	}
+
+	/// Sets a limit on how many requests the client can send in a given period.
+	///
+	/// Requests beyond the limit wait until they're allowed to be sent, rather
+	/// than failing. Bursts of up to `max_per` requests are sent immediately.
+	/// The limit applies across all methods of the built [`Client`] and its
+	/// clones, but separately built clients each have their own limit.
+	///
+	/// The default is no limit.
+	///
+	/// # Panics
+	/// Panics if not in the range `max_per > 0` and `per > 0`.
+	pub fn rate_limit(&mut self, max_per: u32, per: Duration) -> &mut Self {
+		assert!(max_per > 0);
+		assert!(per.is_positive());
+
+		self.rate_limit = Some((max_per, per));
+		self
+	}
+
+	/// Sets whether segments with an unrecognized category or action type
+	/// should be skipped instead of failing the whole request.
+	///
+	/// The API sometimes gains new categories and action types before this
+	/// library is updated to support them. Enabling this keeps older versions
+	/// of the library functional when that happens, at the cost of silently
+	/// dropping the segments it doesn't understand.
+	///
+	/// The default is `false`.
+	pub fn skip_unknown_segments(&mut self, skip_unknown_segments: bool) -> &mut Self {
+		self.skip_unknown_segments = skip_unknown_segments;
+		self
+	}
+
+	/// Sets whether the action type of [`Highlight`] segments should always be
+	/// corrected to [`PointOfInterest`].
+	///
+	/// For backwards-compatibility with old clients, the API reports
+	/// highlights as [`Skip`] segments unless point-of-interest segments are
+	/// explicitly requested. Since a highlight is always a single point, the
+	/// library corrects this by default so highlights look the same however
+	/// they were fetched. Disabling this returns the action type exactly as
+	/// the server sent it, which is mostly useful for debugging and for
+	/// inspecting the server's behaviour. Uncorrected highlights come back
+	/// as zero-length skips.
+	///
+	/// The default is `true`.
+	///
+	/// [`Highlight`]: crate::Category::Highlight
+	/// [`PointOfInterest`]: crate::Action::PointOfInterest
+	/// [`Skip`]: crate::Action::Skip
+	pub fn rewrite_highlight_action(&mut self, rewrite_highlight_action: bool) -> &mut Self {
+		self.rewrite_highlight_action = rewrite_highlight_action;
+		self
+	}
+
+	/// Sets whether video IDs passed to the client, such as to
+	/// [`fetch_segments`], should be run through [`parse_video_id`] first.
+	///
+	/// This allows passing full YouTube URLs, such as ones entered by a user,
+	/// directly. Input that [`parse_video_id`] can't make sense of is used
+	/// as-is. Functions that return results keyed by video ID, like
+	/// [`fetch_segments_for_playlist`], use the parsed video IDs as the keys.
+	///
+	/// The default is `false`.
+	///
+	/// [`fetch_segments`]: Client::fetch_segments
+	/// [`fetch_segments_for_playlist`]: Client::fetch_segments_for_playlist
+	/// [`parse_video_id`]: crate::parse_video_id
+	pub fn auto_parse_video_ids(&mut self, auto_parse_video_ids: bool) -> &mut Self {
+		self.auto_parse_video_ids = auto_parse_video_ids;
+		self
+	}
+
+	/// Sets the maximum size of response bodies to accept from the API, in
+	/// bytes.
+	///
+	/// Responses larger than this fail with [`ResponseTooLarge`] instead of
+	/// being read into memory in full. This protects long-running services
+	/// from misbehaving servers.
+	///
+	/// The default is [`None`], meaning no limit.
+	///
+	/// [`ResponseTooLarge`]: SponsorBlockError::ResponseTooLarge
+	pub fn max_response_bytes(&mut self, max_response_bytes: Option<usize>) -> &mut Self {
+		self.max_response_bytes = max_response_bytes;
+		self
+	}
+
+	/// Sets whether identical requests that are in flight at the same time
+	/// should be combined into one.
+	///
+	/// When enabled, a request that's identical to one already being sent
+	/// waits for that one to finish and shares its response, instead of being
+	/// sent itself. This reduces load under bursts of requests for the same
+	/// thing, such as a popular video. It applies across the built [`Client`]
+	/// and its clones.
+	///
+	/// Only successful responses are shared. If the original request fails,
+	/// each waiting request is sent on its own so it gets its own error.
+	///
+	/// This also combines segment requests made with
+	/// [`conditional_segment_requests`] enabled, since identical requests share
+	/// a remembered response. The exception is
+	/// [`Client::fetch_segments_versioned`], which is always sent on its own
+	/// because each call revalidates the caller's own response.
+	///
+	/// The default is `false`.
+	///
+	/// [`conditional_segment_requests`]: Self::conditional_segment_requests
+	pub fn single_flight(&mut self, single_flight: bool) -> &mut Self {
+		self.single_flight = single_flight;
+		self
+	}
+
+	/// Sets whether segment requests should be conditional on the response to
+	/// the previous identical request.
+	///
+	/// When enabled, the client remembers the `ETag` and `Last-Modified`
+	/// headers of each segment response, and sends them back with the next
+	/// identical request. If the server says the segments haven't changed, the
+	/// remembered response is used instead of downloading it again. This cuts
+	/// bandwidth when polling the same video's segments frequently, such as
+	/// during a premiere. It applies across the built [`Client`] and its
+	/// clones.
+	///
+	/// A response is remembered for each distinct request, including the
+	/// video ID and filters, for as long as the client is alive. This isn't
+	/// suited to clients that fetch segments for an unbounded number of
+	/// videos.
+	///
+	/// This works alongside [`single_flight`], which still combines identical
+	/// segment requests that are in flight at the same time.
+	///
+	/// The default is `false`.
+	///
+	/// [`single_flight`]: Self::single_flight
+	pub fn conditional_segment_requests(
+		&mut self,
+		conditional_segment_requests: bool,
+	) -> &mut Self {
+		self.conditional_segment_requests = conditional_segment_requests;
+		self
+	}
+
+	/// Sets whether invalid TLS certificates should be accepted, such as
+	/// self-signed ones.
+	///
+	/// **This is dangerous.** It disables all certificate validation, leaving
+	/// requests open to interception. It should only ever be used for testing
+	/// against a locally-hosted instance of the API, and never with the
+	/// official one.
+	///
+	/// The default is `false`.
+	pub fn danger_accept_invalid_certs(&mut self, danger_accept_invalid_certs: bool) -> &mut Self {
+		self.danger_accept_invalid_certs = danger_accept_invalid_certs;
+		self
+	}
+
+	/// Sets a responder that answers every request with a canned status code
+	/// and response text, instead of actually contacting the API.
+	///
+	/// The responder is called with the full URL of each request, including
+	/// the query string. What it returns is handled exactly like a response
+	/// from the API, so error statuses, unexpected bodies, and the
+	/// [`max_response_bytes`] limit can all be tested. Everything else about
+	/// sending a request, such as the [`rate_limit`], still applies. This is
+	/// intended for testing code that uses the library without network
+	/// access.
+	///
+	/// [`max_response_bytes`]: Self::max_response_bytes
+	/// [`rate_limit`]: Self::rate_limit
+	#[cfg(feature = "mock")]
+	pub fn responder<R>(&mut self, responder: R) -> &mut Self
+	where
+		R: Fn(&str) -> (StatusCode, String) + Send + Sync + 'static,
+	{
+		self.responder = Some(Arc::new(responder));
+		self
+	}
+
+	/// Sets a receiver for metrics about every request sent to the API.
+	///
+	/// See [`Metrics`] for more information.
+	///
+	/// The default is no metrics collection.
+	#[cfg(feature = "metrics")]
+	pub fn metrics<M>(&mut self, metrics: M) -> &mut Self
+	where
+		M: Metrics + 'static,
+	{
+		self.metrics = Some(Arc::new(metrics));
+		self
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	// Uses
+	#[cfg(feature = "private_searches")]
+	use super::estimate_prefix_collisions;
+	use super::Client;
+	use crate::SponsorBlockError;
+
+	#[cfg(feature = "private_searches")]
+	#[test]
+	fn estimate_prefix_collisions_values() {
+		// (hash prefix length, video count, expected estimate)
+		let cases = [
+			(4, 10_000_000, 154),
+			(5, 10_000_000, 11),
+			(4, 0, 1),
+			(4, 1, 1),
+			(32, 10_000_000, 1),
+			(u8::MAX, u64::MAX, 1),
+			(0, 10, 10),
+			(0, u64::MAX, u64::MAX),
+		];
+
+		for (hash_prefix_length, video_count, expected) in cases {
+			assert_eq!(
+				estimate_prefix_collisions(hash_prefix_length, video_count),
+				expected,
+				"hash prefix length: {hash_prefix_length}, video count: {video_count}"
+			);
+		}
+	}
+
+	#[test]
+	fn with_user_agent_accepts_valid_values() {
+		let client = Client::new("test");
+		assert!(client.with_user_agent("my-app/1.0").is_ok());
+	}
+
+	#[test]
+	fn with_user_agent_rejects_invalid_values() {
+		let client = Client::new("test");
+		assert!(matches!(
+			client.with_user_agent("my-app/1.0\r\nX-Injected: true"),
+			Err(SponsorBlockError::BadData(_))
+		));
+	}
 }
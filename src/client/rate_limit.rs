@@ -0,0 +1,120 @@
+//! Client-side rate limiting for requests to the API.
+
+// Uses
+use std::{
+	sync::Mutex,
+	time::{Duration as StdDuration, Instant},
+};
+
+use reqwest::header::HeaderMap;
+use time::{Duration, OffsetDateTime};
+use tokio::time::sleep;
+
+/// The rate limit quota reported by the server with its latest response.
+///
+/// See [`Client::rate_limit_status`] for more information.
+///
+/// [`Client::rate_limit_status`]: crate::Client::rate_limit_status
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct RateLimitStatus {
+	/// The maximum number of requests allowed in the current window, from the
+	/// `X-RateLimit-Limit` header.
+	pub limit: Option<u32>,
+	/// The number of requests remaining in the current window, from the
+	/// `X-RateLimit-Remaining` header.
+	pub remaining: Option<u32>,
+	/// When the current window resets, from the `X-RateLimit-Reset` header.
+	///
+	/// The header is read as a Unix timestamp in seconds, or as a number of
+	/// seconds from now if it's too small to be a timestamp.
+	pub reset: Option<OffsetDateTime>,
+}
+
+impl RateLimitStatus {
+	/// Reads the rate limit headers from a response, if there are any.
+	pub(crate) fn from_headers(headers: &HeaderMap) -> Option<Self> {
+		/// Values below this are too small to be a recent Unix timestamp, so
+		/// they're treated as a number of seconds from now.
+		const MIN_TIMESTAMP: i64 = 1_000_000_000;
+
+		let header = |name: &str| {
+			headers
+				.get(name)
+				.and_then(|value| value.to_str().ok())
+				.and_then(|value| value.trim().parse::<i64>().ok())
+		};
+
+		let limit = header("x-ratelimit-limit").and_then(|value| value.try_into().ok());
+		let remaining = header("x-ratelimit-remaining").and_then(|value| value.try_into().ok());
+		let reset = header("x-ratelimit-reset").and_then(|value| {
+			if value < MIN_TIMESTAMP {
+				OffsetDateTime::now_utc().checked_add(Duration::seconds(value))
+			} else {
+				OffsetDateTime::from_unix_timestamp(value).ok()
+			}
+		});
+
+		if limit.is_none() && remaining.is_none() && reset.is_none() {
+			return None;
+		}
+
+		Some(Self {
+			limit,
+			remaining,
+			reset,
+		})
+	}
+}
+
+/// A rate limiter that spaces out requests so that no more than a set number
+/// are sent in a given period, while still allowing bursts up to that number.
+///
+/// This is an implementation of the generic cell rate algorithm, which behaves
+/// like a token bucket.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+	/// The time between requests when sending at the maximum sustained rate.
+	emission_interval: StdDuration,
+	/// How far ahead of the sustained rate requests are allowed to burst.
+	burst_tolerance: StdDuration,
+	/// The theoretical arrival time of the next request.
+	next_arrival: Mutex<Instant>,
+}
+
+impl RateLimiter {
+	/// Creates a new rate limiter allowing `max_per` requests every `per`.
+	pub(crate) fn new(max_per: u32, per: StdDuration) -> Self {
+		let emission_interval = per / max_per;
+		Self {
+			emission_interval,
+			burst_tolerance: per.saturating_sub(emission_interval),
+			next_arrival: Mutex::new(Instant::now()),
+		}
+	}
+
+	/// Waits until a request is allowed to be sent.
+	///
+	/// Each call reserves its own slot, so callers are let through in the order
+	/// they arrive.
+	pub(crate) async fn acquire(&self) {
+		let wait = {
+			let mut next_arrival = self
+				.next_arrival
+				.lock()
+				.expect("the rate limiter lock was poisoned");
+			let now = Instant::now();
+			let arrival = (*next_arrival).max(now);
+			*next_arrival = arrival + self.emission_interval;
+			arrival
+				.checked_sub(self.burst_tolerance)
+				.map_or(StdDuration::ZERO, |allowed_at| {
+					allowed_at.saturating_duration_since(now)
+				})
+		};
+
+		if !wait.is_zero() {
+			sleep(wait).await;
+		}
+	}
+}
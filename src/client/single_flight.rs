@@ -0,0 +1,145 @@
+//! Coalescing of identical requests that are in flight at the same time.
+
+// Uses
+use std::{
+	collections::HashMap,
+	pin::pin,
+	sync::{Arc, Mutex},
+};
+
+use tokio::sync::Notify;
+
+/// Tracks in-flight requests so that identical concurrent requests can share
+/// the response of a single one.
+///
+/// Only successful responses are shared. Errors can't be cloned, so requests
+/// that were waiting on a failed one are sent on their own instead.
+#[derive(Debug, Default)]
+pub(crate) struct SingleFlight {
+	in_flight: Mutex<HashMap<String, Arc<Flight>>>,
+}
+
+/// A single in-flight request that others may be waiting on.
+#[derive(Debug, Default)]
+struct Flight {
+	outcome: Mutex<Outcome>,
+	done: Notify,
+}
+
+/// The outcome of an in-flight request.
+#[derive(Clone, Debug, Default)]
+enum Outcome {
+	/// The request hasn't finished yet.
+	#[default]
+	Pending,
+	/// The request succeeded with the contained response text.
+	Succeeded(String),
+	/// The request failed or was cancelled.
+	Failed,
+}
+
+/// The part a request plays in a flight.
+pub(crate) enum Role<'a> {
+	/// The request should actually be sent, and its result reported through
+	/// the guard.
+	Leader(LeaderGuard<'a>),
+	/// An identical request is already in flight, and its result can be
+	/// waited on.
+	Follower(Follower),
+}
+
+impl SingleFlight {
+	/// Joins the flight for `key`, starting a new one if there isn't one
+	/// already.
+	pub(crate) fn join(&self, key: String) -> Role<'_> {
+		let mut in_flight = self
+			.in_flight
+			.lock()
+			.expect("the single-flight lock was poisoned");
+		if let Some(flight) = in_flight.get(&key) {
+			return Role::Follower(Follower {
+				flight: Arc::clone(flight),
+			});
+		}
+
+		let flight = Arc::new(Flight::default());
+		in_flight.insert(key.clone(), Arc::clone(&flight));
+		Role::Leader(LeaderGuard {
+			single_flight: self,
+			key,
+			flight,
+			finished: false,
+		})
+	}
+}
+
+/// Held by the request that's actually sent, to share its result when it
+/// finishes.
+///
+/// If it's dropped without finishing, such as when the request is cancelled,
+/// any followers are told to send their own requests.
+pub(crate) struct LeaderGuard<'a> {
+	single_flight: &'a SingleFlight,
+	key: String,
+	flight: Arc<Flight>,
+	finished: bool,
+}
+
+impl LeaderGuard<'_> {
+	/// Shares the result of the request with any followers.
+	pub(crate) fn finish(mut self, text: Option<String>) {
+		self.complete(text.map_or(Outcome::Failed, Outcome::Succeeded));
+		self.finished = true;
+	}
+
+	fn complete(&self, outcome: Outcome) {
+		*self
+			.flight
+			.outcome
+			.lock()
+			.expect("the single-flight lock was poisoned") = outcome;
+		self.single_flight
+			.in_flight
+			.lock()
+			.expect("the single-flight lock was poisoned")
+			.remove(&self.key);
+		self.flight.done.notify_waiters();
+	}
+}
+
+impl Drop for LeaderGuard<'_> {
+	fn drop(&mut self) {
+		if !self.finished {
+			self.complete(Outcome::Failed);
+		}
+	}
+}
+
+/// A request waiting on an identical one that's already in flight.
+pub(crate) struct Follower {
+	flight: Arc<Flight>,
+}
+
+impl Follower {
+	/// Waits for the in-flight request to finish, returning its response text
+	/// if it succeeded.
+	pub(crate) async fn wait(self) -> Option<String> {
+		let mut notified = pin!(self.flight.done.notified());
+		notified.as_mut().enable();
+		if let Outcome::Pending = self.outcome() {
+			notified.await;
+		}
+		match self.outcome() {
+			Outcome::Succeeded(text) => Some(text),
+			Outcome::Pending | Outcome::Failed => None,
+		}
+	}
+
+	fn outcome(&self) -> Outcome {
+		self.flight
+			.outcome
+			.lock()
+			.expect("the single-flight lock was poisoned")
+			.clone()
+	}
+}
@@ -1,17 +1,18 @@
 //! The functions for retrieving information about the API status.
 
 // Uses
+use futures::future::join;
 use serde::Deserialize;
-use serde_json::from_str as from_json_str;
 use time::{Duration, OffsetDateTime};
 
 use crate::{
 	error::Result,
 	util::{
 		de::{datetime_from_millis_timestamp, duration_from_millis_str, duration_from_seconds_str},
-		get_response_text,
+		from_json_str,
 	},
 	Client,
+	TotalStats,
 };
 
 /// The results of an API status request.
@@ -37,14 +38,34 @@ pub struct ApiStatus {
 	#[serde(rename = "processTime", deserialize_with = "duration_from_millis_str")]
 	pub request_time_taken: Duration,
 	/// The load average for the server. The first entry is the average for 5
-	/// minutes, and the second is for 15 minutes.
+	/// minutes, and the second is for 15 minutes. The server intentionally
+	/// leaves out the 1-minute average.
+	///
+	/// See [`load_avg_5m`] and [`load_avg_15m`] for named access to each value.
 	///
 	/// If you want more information about the source of this information, visit
 	/// <https://github.com/ajayyy/SponsorBlockServer/blob/06af78c770b82722be8b03d2b1b82eb7409f675b/src/routes/getStatus.ts#L18>
+	///
+	/// [`load_avg_5m`]: Self::load_avg_5m
+	/// [`load_avg_15m`]: Self::load_avg_15m
 	#[serde(rename = "loadavg")]
 	pub load_average: [f32; 2],
 }
 
+impl ApiStatus {
+	/// The load average for the server over the last 5 minutes.
+	#[must_use]
+	pub fn load_avg_5m(&self) -> f32 {
+		self.load_average[0]
+	}
+
+	/// The load average for the server over the last 15 minutes.
+	#[must_use]
+	pub fn load_avg_15m(&self) -> f32 {
+		self.load_average[1]
+	}
+}
+
 impl Default for ApiStatus {
 	fn default() -> Self {
 		Self {
@@ -58,27 +79,106 @@ impl Default for ApiStatus {
 	}
 }
 
+/// The combined results of a health request.
+///
+/// Each part is fetched separately, so one failing doesn't prevent the other
+/// from being available.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct Health {
+	/// The API status, including uptime, database version, and load.
+	pub status: Result<ApiStatus>,
+	/// The overall statistics for the service.
+	pub total_stats: Result<TotalStats>,
+}
+
+// Function-Specific Deserialization Structs
+#[derive(Debug, Deserialize)]
+struct RawDbVersion {
+	#[serde(rename = "db")]
+	db_version: u32,
+}
+
+// Function Constants
+pub(super) const API_ENDPOINT: &str = "/status";
+
 // Function Implementation
 impl Client {
 	/// Fetches the API status.
 	///
+	/// The response is cached, and later calls ask the server whether it has
+	/// changed before downloading it again. If it hasn't, the cached status is
+	/// returned, including its original [`request_start_time`] and
+	/// [`request_time_taken`]. The cache is shared between clones of the
+	/// client.
+	///
 	/// # Errors
 	/// Can return pretty much any error type from [`SponsorBlockError`]. See
 	/// the error type definitions for explanations of when they might be
 	/// encountered.
 	///
+	/// [`request_start_time`]: ApiStatus::request_start_time
+	/// [`request_time_taken`]: ApiStatus::request_time_taken
 	/// [`SponsorBlockError`]: crate::SponsorBlockError
 	pub async fn fetch_api_status(&self) -> Result<ApiStatus> {
-		// Function Constants
-		const API_ENDPOINT: &str = "/status";
+		// Build the request
+		let request = Self::with_timeout(
+			self.http.get(format!("{}{}", &self.api_url, API_ENDPOINT)),
+			self.status_timeout,
+		);
+
+		// Send the request
+		let response = self
+			.send_cached_request(request, Some(&self.status_cache))
+			.await?;
 
+		// Parse the response
+		from_json_str::<ApiStatus>(response.as_str())
+	}
+
+	/// Fetches only the database version from the API status.
+	///
+	/// This is a lighter alternative to [`fetch_api_status`] for when the
+	/// database version is all that's needed, such as for compatibility
+	/// checks.
+	///
+	/// # Errors
+	/// Can return pretty much any error type from [`SponsorBlockError`]. See
+	/// the error type definitions for explanations of when they might be
+	/// encountered.
+	///
+	/// [`fetch_api_status`]: Self::fetch_api_status
+	/// [`SponsorBlockError`]: crate::SponsorBlockError
+	pub async fn fetch_db_version(&self) -> Result<u32> {
 		// Build the request
-		let request = self.http.get(format!("{}{}", &self.base_url, API_ENDPOINT));
+		let request = Self::with_timeout(
+			self.http.get(format!("{}{}", &self.api_url, API_ENDPOINT)),
+			self.status_timeout,
+		);
 
 		// Send the request
-		let response = get_response_text(request.send().await?).await?;
+		let response = self.send_request(request).await?;
 
 		// Parse the response
-		Ok(from_json_str::<ApiStatus>(response.as_str())?)
+		Ok(from_json_str::<RawDbVersion>(response.as_str())?.db_version)
+	}
+
+	/// Fetches the API status and the overall statistics for the service at
+	/// once.
+	///
+	/// This is intended for status pages and monitoring. The requests are
+	/// issued concurrently, and failures are recorded in the result instead of
+	/// failing the whole call. See [`fetch_api_status`] and
+	/// [`fetch_total_stats`] for more information.
+	///
+	/// [`fetch_api_status`]: Self::fetch_api_status
+	/// [`fetch_total_stats`]: Self::fetch_total_stats
+	pub async fn fetch_health(&self) -> Health {
+		let (status, total_stats) = join(self.fetch_api_status(), self.fetch_total_stats()).await;
+
+		Health {
+			status,
+			total_stats,
+		}
 	}
 }
@@ -2,9 +2,33 @@
 
 // Modules
 mod api_status;
+mod prefetch;
+#[cfg(feature = "refresher")]
+mod refresher;
 mod segments;
+mod total_stats;
 mod user_info;
 mod user_stats;
 
 // Public Exports
-pub use self::{api_status::*, segments::*, user_info::*, user_stats::*};
+#[cfg(feature = "refresher")]
+pub use self::refresher::*;
+pub use self::{
+	api_status::*,
+	prefetch::*,
+	segments::*,
+	total_stats::*,
+	user_info::*,
+	user_stats::*,
+};
+
+/// The paths of all the endpoints used by the user functions, relative to the
+/// API URL.
+pub(crate) const ENDPOINTS: &[&str] = &[
+	api_status::API_ENDPOINT,
+	segments::SKIP_SEGMENTS_API_ENDPOINT,
+	segments::SEGMENT_INFO_API_ENDPOINT,
+	total_stats::API_ENDPOINT,
+	user_info::API_ENDPOINT,
+	user_stats::API_ENDPOINT,
+];
@@ -0,0 +1,133 @@
+//! The functions for prefetching segments for offline use.
+
+// Uses
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime};
+
+use crate::{AcceptedActions, AcceptedCategories, Client, Segment, SponsorBlockError};
+
+/// The segments for a set of videos, fetched ahead of time for use while
+/// offline.
+///
+/// This can be serialized and stored, then later deserialized and queried
+/// without a [`Client`].
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct PrefetchResult {
+	/// The prefetched videos, keyed by video ID.
+	pub videos: HashMap<String, PrefetchedVideo>,
+	/// The errors for videos that couldn't be fetched, keyed by video ID.
+	///
+	/// These aren't serialized, since they're only relevant at the time of
+	/// fetching.
+	#[serde(skip)]
+	pub errors: HashMap<String, SponsorBlockError>,
+}
+
+impl PrefetchResult {
+	/// Gets the prefetched segments for a video.
+	///
+	/// Returns [`None`] if the video wasn't prefetched. Videos without any
+	/// segments in the database were still prefetched, and have an empty list.
+	#[must_use]
+	pub fn segments(&self, video_id: &str) -> Option<&[Segment]> {
+		self.videos
+			.get(video_id)
+			.map(|video| video.segments.as_slice())
+	}
+
+	/// Adds the videos from a newer prefetch, replacing any that are already
+	/// present.
+	///
+	/// This is useful for refreshing stale videos without fetching the whole
+	/// set again. Errors are replaced in the same way, and videos that are
+	/// now present no longer have an error recorded.
+	pub fn extend(&mut self, newer: Self) {
+		for video_id in newer.videos.keys() {
+			self.errors.remove(video_id);
+		}
+		self.videos.extend(newer.videos);
+		self.errors.extend(newer.errors);
+	}
+}
+
+/// The prefetched segments for a single video.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct PrefetchedVideo {
+	/// When the segments were fetched.
+	#[serde(with = "time::serde::timestamp")]
+	pub fetched_at: OffsetDateTime,
+	/// The segments for the video, of all categories and action types.
+	pub segments: Vec<Segment>,
+}
+
+impl PrefetchedVideo {
+	/// How long ago the segments were fetched.
+	#[must_use]
+	pub fn age(&self) -> Duration {
+		OffsetDateTime::now_utc() - self.fetched_at
+	}
+
+	/// Whether the segments were fetched longer ago than `max_age`, and should
+	/// be fetched again when possible.
+	#[must_use]
+	pub fn is_stale(&self, max_age: Duration) -> bool {
+		self.age() > max_age
+	}
+}
+
+// Function Implementation
+impl Client {
+	/// Fetches the segments for a list of video IDs, for storing and using
+	/// while offline.
+	///
+	/// All categories and action types are fetched, so that any of them can be
+	/// used later on. The requests are made in the same way as
+	/// [`fetch_segments_for_playlist`].
+	///
+	/// This function *does not* return additional segment info.
+	///
+	/// Videos without any segments in the database are included with an empty
+	/// list, since that's still useful to know offline. Videos that fail to
+	/// fetch for any other reason are recorded in [`PrefetchResult::errors`]
+	/// instead.
+	///
+	/// [`fetch_segments_for_playlist`]: Self::fetch_segments_for_playlist
+	pub async fn prefetch<V>(&self, video_ids: &[V]) -> PrefetchResult
+	where
+		V: AsRef<str>,
+	{
+		let results = self
+			.fetch_segments_for_playlist(
+				video_ids,
+				AcceptedCategories::all(),
+				AcceptedActions::all(),
+			)
+			.await;
+		let fetched_at = OffsetDateTime::now_utc();
+
+		let mut prefetch_result = PrefetchResult::default();
+		for (video_id, result) in results {
+			let segments = match result {
+				Ok(segments) => segments,
+				Err(SponsorBlockError::VideoNotFound) => Vec::new(),
+				Err(error) => {
+					prefetch_result.errors.insert(video_id, error);
+					continue;
+				}
+			};
+			prefetch_result.videos.insert(
+				video_id,
+				PrefetchedVideo {
+					fetched_at,
+					segments,
+				},
+			);
+		}
+
+		prefetch_result
+	}
+}
@@ -0,0 +1,152 @@
+//! Refreshing of segments in the background.
+
+// Uses
+use std::sync::{Arc, Mutex, PoisonError};
+
+use time::Duration;
+use tokio::{
+	select,
+	spawn,
+	sync::{watch, Notify},
+	task::JoinHandle,
+	time::sleep,
+};
+
+use crate::{AcceptedActions, AcceptedCategories, Client, Segment, SponsorBlockError};
+
+/// A handle to a task that keeps the segments for a video up to date in the
+/// background.
+///
+/// The task is stopped when the handle is dropped.
+///
+/// See [`Client::spawn_refresher`] for more information.
+#[derive(Debug)]
+pub struct SegmentHandle {
+	segments: watch::Receiver<Vec<Segment>>,
+	refresh_now: Arc<Notify>,
+	last_error: Arc<Mutex<Option<SponsorBlockError>>>,
+	task: JoinHandle<()>,
+}
+
+impl SegmentHandle {
+	/// Gets a receiver for the latest segments, which can be used to wait for
+	/// changes.
+	///
+	/// The receiver starts out with an empty list until the first fetch
+	/// succeeds.
+	#[must_use]
+	pub fn subscribe(&self) -> watch::Receiver<Vec<Segment>> {
+		self.segments.clone()
+	}
+
+	/// Gets a copy of the latest segments.
+	#[must_use]
+	pub fn latest(&self) -> Vec<Segment> {
+		self.segments.borrow().clone()
+	}
+
+	/// Fetches the segments again right away, instead of waiting for the rest
+	/// of the interval.
+	///
+	/// The interval starts over once the fetch is done.
+	pub fn refresh_now(&self) {
+		self.refresh_now.notify_one();
+	}
+
+	/// Takes the error from the latest fetch, if it failed.
+	///
+	/// The error is cleared once taken, and whenever a fetch succeeds, so a
+	/// refresher that keeps failing keeps reporting new errors.
+	#[must_use]
+	pub fn take_last_error(&self) -> Option<SponsorBlockError> {
+		self.last_error
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.take()
+	}
+}
+
+impl Drop for SegmentHandle {
+	fn drop(&mut self) {
+		self.task.abort();
+	}
+}
+
+// Function Implementation
+impl Client {
+	/// Spawns a task that fetches the segments for a given video ID on an
+	/// interval, keeping them up to date in the background.
+	///
+	/// The segments are fetched right away, then again every `interval`. All
+	/// categories and action types are fetched. The latest segments are
+	/// available through the returned [`SegmentHandle`], and the task stops
+	/// when it's dropped.
+	///
+	/// If a fetch fails, the previous segments are kept and the fetch is tried
+	/// again at the next interval. The error can be checked with
+	/// [`SegmentHandle::take_last_error`]. If the video doesn't have any
+	/// segments, the list is empty.
+	///
+	/// This function *does not* return additional segment info.
+	///
+	/// # Panics
+	/// - If `interval` isn't positive.
+	/// - If called outside of a Tokio runtime.
+	#[must_use]
+	pub fn spawn_refresher<V>(&self, video_id: V, interval: Duration) -> SegmentHandle
+	where
+		V: Into<String>,
+	{
+		assert!(interval.is_positive());
+
+		let interval = interval.try_into().expect(
+			"the Duration value provided for the refresh interval is incompatible with the std \
+			 library implementation",
+		);
+		let video_id = video_id.into();
+		let client = self.clone();
+		let (sender, receiver) = watch::channel(Vec::new());
+		let refresh_now = Arc::new(Notify::new());
+		let last_error = Arc::new(Mutex::new(None));
+
+		let task = spawn({
+			let refresh_now = Arc::clone(&refresh_now);
+			let last_error = Arc::clone(&last_error);
+			async move {
+				loop {
+					let error = match client
+						.fetch_segments(
+							&video_id,
+							AcceptedCategories::all(),
+							AcceptedActions::all(),
+						)
+						.await
+					{
+						Ok(segments) => {
+							sender.send_replace(segments);
+							None
+						}
+						Err(SponsorBlockError::VideoNotFound) => {
+							sender.send_replace(Vec::new());
+							None
+						}
+						Err(error) => Some(error),
+					};
+					*last_error.lock().unwrap_or_else(PoisonError::into_inner) = error;
+
+					select! {
+						() = sleep(interval) => {}
+						() = refresh_now.notified() => {}
+					}
+				}
+			}
+		});
+
+		SegmentHandle {
+			segments: receiver,
+			refresh_now,
+			last_error,
+			task,
+		}
+	}
+}
@@ -1,26 +1,77 @@
 //! The functions for retrieving segments and segment info for videos.
 
 // Uses
-use serde::Deserialize;
-use serde_json::from_str as from_json_str;
+use std::{
+	borrow::Cow,
+	collections::HashMap,
+	iter,
+	result::Result as StdResult,
+	sync::atomic::{AtomicUsize, Ordering as AtomicOrdering},
+};
+
+use futures::future::join_all;
+use reqwest::RequestBuilder;
+use serde::{de::Error as DeError, Deserialize, Deserializer};
+use serde_json::{Error as JsonError, Value};
 #[cfg(feature = "private_searches")]
 use sha2::{Digest, Sha256};
 
 #[cfg(feature = "private_searches")]
 use crate::util::bytes_to_hex_string;
 use crate::{
-	api::{convert_action_bitflags_to_url, convert_category_bitflags_to_url},
+	api::{
+		convert_action_bitflags_to_url,
+		convert_category_bitflags_to_url,
+		convert_to_action_kind,
+		convert_to_category,
+		UnknownValueError,
+	},
+	client::cache::ResponseCache,
 	error::{Result, SponsorBlockError},
-	segment::{AcceptedActions, AcceptedCategories, ActionKind, Category, Segment},
+	segment::{
+		merge_overlapping,
+		should_skip,
+		validate_segment_uuid,
+		AcceptedActions,
+		AcceptedCategories,
+		ActionKind,
+		Category,
+		CategoryPrefs,
+		MergeStrategy,
+		Segment,
+		SkipBehavior,
+	},
 	util::{
-		de::{bool_from_integer_str, none_on_0_0_from_str},
-		get_response_text,
+		de::{
+			bool_from_integer_str,
+			none_on_0_0_from_str,
+			option_f32_from_number_or_str,
+			option_f32_pair_from_number_or_str,
+		},
+		from_json_str,
 		to_url_array,
 	},
+	video_id::parse_video_id,
 	AdditionalSegmentInfo,
+	CacheValidator,
 	Client,
 };
 
+/// The results of a [`fetch_segments_full`] request.
+///
+/// [`fetch_segments_full`]: Client::fetch_segments_full
+#[derive(Clone, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct SegmentSet {
+	/// Every segment returned by the API.
+	pub all: Vec<Segment>,
+	/// The same segments, cleaned up the same way as
+	/// [`fetch_clean_segments`] does.
+	///
+	/// [`fetch_clean_segments`]: Client::fetch_clean_segments
+	pub best: Vec<Segment>,
+}
+
 // Function-Specific Deserialization Structs
 #[cfg(feature = "private_searches")]
 #[derive(Debug, Default, Deserialize)]
@@ -32,14 +83,21 @@ struct RawHashMatch {
 	segments: Vec<RawSegment>,
 }
 
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Deserialize)]
 #[serde(default, rename_all = "camelCase")]
 struct RawSegment {
-	category: Category,
-	action_type: ActionKind,
-	#[serde(rename = "segment")]
+	#[serde(deserialize_with = "category_or_unknown")]
+	category: StdResult<Category, UnknownValueError>,
+	#[serde(deserialize_with = "action_kind_or_unknown")]
+	action_type: StdResult<ActionKind, UnknownValueError>,
+	#[serde(
+		rename = "segment",
+		deserialize_with = "option_f32_pair_from_number_or_str"
+	)]
 	time_points: Option<[f32; 2]>,
+	#[serde(deserialize_with = "option_f32_from_number_or_str")]
 	start_time: Option<f32>,
+	#[serde(deserialize_with = "option_f32_from_number_or_str")]
 	end_time: Option<f32>,
 	#[serde(rename = "UUID")]
 	uuid: String,
@@ -52,6 +110,47 @@ struct RawSegment {
 	additional_info: AdditionalSegmentInfo,
 }
 
+impl Default for RawSegment {
+	fn default() -> Self {
+		Self {
+			category: Ok(Category::default()),
+			action_type: Ok(ActionKind::default()),
+			time_points: Option::default(),
+			start_time: Option::default(),
+			end_time: Option::default(),
+			uuid: String::default(),
+			locked: bool::default(),
+			votes: i32::default(),
+			video_duration_upon_submission: Option::default(),
+			additional_info: AdditionalSegmentInfo::default(),
+		}
+	}
+}
+
+/// Deserializes a category, keeping unrecognized values around as an error
+/// instead of failing so that the decision can be made later.
+fn category_or_unknown<'de, D>(
+	deserializer: D,
+) -> StdResult<StdResult<Category, UnknownValueError>, D::Error>
+where
+	D: Deserializer<'de>,
+{
+	let raw = String::deserialize(deserializer)?;
+	Ok(convert_to_category(raw.as_str()))
+}
+
+/// Deserializes an action type, keeping unrecognized values around as an
+/// error instead of failing so that the decision can be made later.
+fn action_kind_or_unknown<'de, D>(
+	deserializer: D,
+) -> StdResult<StdResult<ActionKind, UnknownValueError>, D::Error>
+where
+	D: Deserializer<'de>,
+{
+	let raw = String::deserialize(deserializer)?;
+	Ok(convert_to_action_kind(raw.as_str()))
+}
+
 impl RawSegment {
 	/// Converts a raw segment that more closely matches the structure returned
 	/// by the API to the proper rusty [`Segment`] type.
@@ -59,17 +158,41 @@ impl RawSegment {
 	/// `additional_info` determines whether or not to include
 	/// `RawSegment.additional_info`, since it is always populated by Serde but
 	/// not with useful values under certain circumstances.
-	fn convert_to_segment(self, additional_info: bool) -> Result<Segment> {
+	///
+	/// `skip_unknown` determines whether a segment with an unrecognized
+	/// category or action type is skipped (returning [`None`]) or treated as
+	/// a deserialization error.
+	fn convert_to_segment(
+		self,
+		additional_info: bool,
+		skip_unknown: bool,
+		rewrite_highlight_action: bool,
+	) -> Result<Option<Segment>> {
+		// Make sure the category and action type are known
+		let (category, mut action_type) = match (self.category, self.action_type) {
+			(Ok(category), Ok(action_type)) => (category, action_type),
+			(Err(unknown_value), _) | (_, Err(unknown_value)) => {
+				if skip_unknown {
+					return Ok(None);
+				}
+				return Err(SponsorBlockError::Deserialization(JsonError::custom(
+					unknown_value,
+				)));
+			}
+		};
+
 		// Process the raw time information
-		let time_points = if let Some(points) = self.time_points {
-			points
-		} else {
-			[
-				self.start_time
-					.expect("time_points was empty but so is start_time"),
-				self.end_time
-					.expect("time_points was empty but so is end_time"),
-			]
+		let time_points = match (self.time_points, self.start_time, self.end_time) {
+			(Some(points), ..) => points,
+			(None, Some(start_time), Some(end_time)) => [start_time, end_time],
+			// Full video labels don't have meaningful times anyway
+			(None, ..) if action_type == ActionKind::FullVideo => [0.0, 0.0],
+			(None, ..) => {
+				return Err(SponsorBlockError::BadData(format!(
+					"segment {} has no time information",
+					self.uuid
+				)));
+			}
 		};
 		if time_points[0] > time_points[1] {
 			return Err(SponsorBlockError::BadData(format!(
@@ -92,34 +215,128 @@ impl RawSegment {
 		if let Some(video_duration_upon_submission) = self.video_duration_upon_submission {
 			if video_duration_upon_submission < 0.0 {
 				return Err(SponsorBlockError::BadData(format!(
-					"video duration upon submission ({}) < 0",
-					video_duration_upon_submission
+					"video duration upon submission ({video_duration_upon_submission}) < 0"
 				)));
 			}
 		}
 
 		// For backwards-compatibility, the API returns `skip` as the action type for
 		// Highlight unless one of the requested action types is `poi`.
-		// This makes it so we always return the correct action type regardless.
+		// This makes it so we always return the correct action type regardless,
+		// unless the client is configured to return what the server sent.
 		// https://github.com/ajayyy/SponsorBlockServer/pull/448
-		let mut action_type = self.action_type;
-		if self.category == Category::Highlight {
+		if rewrite_highlight_action && category == Category::Highlight {
 			action_type = ActionKind::PointOfInterest;
 		}
 
 		// Build the clean segment
-		Ok(Segment {
-			category: self.category,
+		Ok(Some(Segment {
+			category,
 			action: action_type.to_action(time_points),
 			uuid: self.uuid,
 			locked: self.locked,
 			votes: self.votes,
 			video_duration_on_submission: self.video_duration_upon_submission,
-			additional_info: additional_info.then(|| self.additional_info),
+			additional_info: additional_info.then_some(self.additional_info),
+		}))
+	}
+}
+
+/// Hashes a video ID for use with private searches.
+#[cfg(feature = "private_searches")]
+fn hash_video_id(video_id: &str) -> String {
+	let mut hasher = Sha256::new();
+	hasher.update(video_id.as_bytes());
+	bytes_to_hex_string(&hasher.finalize()[..])
+}
+
+/// Gets the prefix of a video ID hash that gets sent to the API.
+///
+/// This returns an error instead of panicking if the prefix length isn't in the
+/// range `4 <= hash_prefix_length <= 32` accepted by
+/// [`ClientBuilder::hash_prefix_length`], or if the hash is too short.
+///
+/// [`ClientBuilder::hash_prefix_length`]: crate::ClientBuilder::hash_prefix_length
+#[cfg(feature = "private_searches")]
+fn hash_prefix(video_id_hash: &str, hash_prefix_length: u8) -> Result<&str> {
+	if !(4..=32).contains(&hash_prefix_length) {
+		return Err(SponsorBlockError::InvalidHashPrefixLength(
+			hash_prefix_length,
+		));
+	}
+
+	video_id_hash
+		.get(..hash_prefix_length as usize)
+		.ok_or_else(|| {
+			SponsorBlockError::BadData(format!(
+				"hash prefix length ({hash_prefix_length}) > hash length ({})",
+				video_id_hash.len()
+			))
 		})
+}
+
+/// Adds the query parameters shared by all segment requests.
+///
+/// `required_segments` is the list of required segment UUIDs, already
+/// formatted with [`to_url_array`].
+fn add_segment_query(
+	mut request: RequestBuilder,
+	accepted_categories: AcceptedCategories,
+	accepted_actions: AcceptedActions,
+	required_segments: Option<&str>,
+	service: &str,
+) -> RequestBuilder {
+	request = request
+		.query(&[(
+			"categories",
+			convert_category_bitflags_to_url(accepted_categories),
+		)])
+		.query(&[(
+			"actionTypes",
+			convert_action_bitflags_to_url(accepted_actions),
+		)])
+		.query(&[("service", service)]);
+	if let Some(required_segments) = required_segments {
+		request = request.query(&[("requiredSegments", required_segments)]);
+	}
+	request
+}
+
+/// Converts the 404 error the API returns when it has no segments for a video
+/// into the more meaningful [`SponsorBlockError::VideoNotFound`].
+fn not_found_as_video_not_found(error: SponsorBlockError) -> SponsorBlockError {
+	if matches!(error, SponsorBlockError::HttpClient(404)) {
+		SponsorBlockError::VideoNotFound
+	} else {
+		error
+	}
+}
+
+/// Makes sure at least one category and one action type are accepted.
+///
+/// An empty filter isn't treated as "nothing" by the API, and would otherwise
+/// look the same as a video without any segments.
+fn check_filter(
+	accepted_categories: AcceptedCategories,
+	accepted_actions: AcceptedActions,
+) -> Result<()> {
+	if accepted_categories.is_empty() {
+		return Err(SponsorBlockError::BadData(
+			"no categories requested".to_owned(),
+		));
 	}
+	if accepted_actions.is_empty() {
+		return Err(SponsorBlockError::BadData(
+			"no action types requested".to_owned(),
+		));
+	}
+	Ok(())
 }
 
+// Function Constants
+pub(super) const SKIP_SEGMENTS_API_ENDPOINT: &str = "/skipSegments";
+pub(super) const SEGMENT_INFO_API_ENDPOINT: &str = "/segmentInfo";
+
 // Function Implementation
 impl Client {
 	/// Fetches the segments for a given video ID.
@@ -131,13 +348,19 @@ impl Client {
 	/// the error type definitions for explanations of when they might be
 	/// encountered.
 	///
-	/// The only error types among them you may want to handle differently are
-	/// [`HttpClient(404)`] and [`NoMatchingVideoHash`], as they indicate that
-	/// no videos could be found in the database matching what was provided.
+	/// The only error type among them you may want to handle differently is
+	/// [`VideoNotFound`], as it indicates that no videos could be found in the
+	/// database matching what was provided.
+	///
+	/// If no categories or no action types are accepted
+	/// ([`AcceptedCategories::NONE`] or [`AcceptedActions::NONE`]), no request
+	/// is sent and [`BadData`] is returned. The API doesn't treat an empty
+	/// filter as "nothing", and this keeps the mistake from looking like a
+	/// video without any segments.
 	///
 	/// [`SponsorBlockError`]: crate::SponsorBlockError
-	/// [`HttpClient(404)`]: crate::SponsorBlockError::HttpClient
-	/// [`NoMatchingVideoHash`]: crate::SponsorBlockError::NoMatchingVideoHash
+	/// [`VideoNotFound`]: crate::SponsorBlockError::VideoNotFound
+	/// [`BadData`]: crate::SponsorBlockError::BadData
 	pub async fn fetch_segments<V>(
 		&self,
 		video_id: V,
@@ -147,11 +370,11 @@ impl Client {
 	where
 		V: AsRef<str>,
 	{
-		self.fetch_segments_with_required::<V, &str>(
+		self.fetch_segments_with_required(
 			video_id,
 			accepted_categories,
 			accepted_actions,
-			&[],
+			iter::empty::<&str>(),
 		)
 		.await
 	}
@@ -162,6 +385,10 @@ impl Client {
 	/// retrieved, even if they don't meet the minimum vote threshold. If this
 	/// isn't something you need, use the regular [`fetch_segments`] instead.
 	///
+	/// The UUIDs can come from anything that can be iterated over, such as a
+	/// slice, or several sources chained together without collecting them
+	/// first. They're written straight into the request URL.
+	///
 	/// This function *does not* return additional segment info.
 	///
 	/// # Errors
@@ -169,84 +396,864 @@ impl Client {
 	/// function](Self::fetch_segments).
 	///
 	/// [`fetch_segments`]: Self::fetch_segments
-	pub async fn fetch_segments_with_required<V, S>(
+	pub async fn fetch_segments_with_required<V, I>(
 		&self,
 		video_id: V,
 		accepted_categories: AcceptedCategories,
 		accepted_actions: AcceptedActions,
-		required_segments: &[S],
+		required_segments: I,
 	) -> Result<Vec<Segment>>
 	where
 		V: AsRef<str>,
-		S: AsRef<str>,
+		I: IntoIterator,
+		I::Item: AsRef<str>,
 	{
-		// Function Constants
-		const API_ENDPOINT: &str = "/skipSegments";
+		let mut required_segments = required_segments.into_iter().peekable();
+		let required_segments = required_segments
+			.peek()
+			.is_some()
+			.then(|| to_url_array(required_segments));
+		let video_id = self.resolve_video_id(video_id.as_ref());
+
+		self.fetch_segments_for_service(
+			&video_id,
+			accepted_categories,
+			accepted_actions,
+			required_segments.as_deref(),
+			&self.service,
+		)
+		.await
+	}
+
+	/// Builds the URL that [`fetch_segments`] would request for a given video
+	/// ID, without sending anything.
+	///
+	/// This is useful for debugging, such as for reproducing a request with
+	/// another tool. Under the `private_searches` feature, the URL contains the
+	/// hash prefix rather than the video ID itself.
+	///
+	/// The URL is built even if no categories or no action types are accepted,
+	/// in which case [`fetch_segments`] wouldn't actually send a request.
+	///
+	/// # Errors
+	/// Returns [`BadData`] if the video ID can't be hashed into a prefix of the
+	/// configured length, or [`HttpCommunication`] if the request can't be
+	/// built.
+	///
+	/// [`fetch_segments`]: Self::fetch_segments
+	/// [`BadData`]: crate::SponsorBlockError::BadData
+	/// [`HttpCommunication`]: crate::SponsorBlockError::HttpCommunication
+	pub fn build_segments_url<V>(
+		&self,
+		video_id: V,
+		accepted_categories: AcceptedCategories,
+		accepted_actions: AcceptedActions,
+	) -> Result<String>
+	where
+		V: AsRef<str>,
+	{
+		let video_id = self.resolve_video_id(video_id.as_ref());
+
+		let request = self
+			.video_segments_request(
+				&video_id,
+				accepted_categories,
+				accepted_actions,
+				None,
+				&self.service,
+			)?
+			.build()?;
+
+		Ok(request.url().to_string())
+	}
+
+	/// Fetches the segments for a given video ID as untyped JSON, exactly as
+	/// the API returned them.
+	///
+	/// This is an escape hatch for feeding the data into your own processing,
+	/// or for debugging changes to the API's format, since none of the
+	/// library's conversions or validation are applied. Under the
+	/// `private_searches` feature, only the segments for the requested video
+	/// are returned, rather than every video matching the hash prefix.
+	///
+	/// # Errors
+	/// See the Errors section of the [base version of this
+	/// function](Self::fetch_segments).
+	pub async fn fetch_segments_json<V>(
+		&self,
+		video_id: V,
+		accepted_categories: AcceptedCategories,
+		accepted_actions: AcceptedActions,
+	) -> Result<Value>
+	where
+		V: AsRef<str>,
+	{
+		check_filter(accepted_categories, accepted_actions)?;
+
+		let video_id = self.resolve_video_id(video_id.as_ref());
+		let video_id = video_id.as_ref();
+
+		let request = self.video_segments_request(
+			video_id,
+			accepted_categories,
+			accepted_actions,
+			None,
+			&self.service,
+		)?;
+		let response = self
+			.send_segments_request(request)
+			.await
+			.map_err(not_found_as_video_not_found)?;
 
-		// Build the request and send it
-		let mut request;
 		#[cfg(not(feature = "private_searches"))]
 		{
-			request = self
-				.http
-				.get(format!("{}{}", &self.base_url, API_ENDPOINT))
-				.query(&[("videoID", video_id.as_ref())]);
+			from_json_str::<Value>(response.as_str())
 		}
 		#[cfg(feature = "private_searches")]
 		{
-			let video_id_hash = {
-				let mut hasher = Sha256::new();
-				hasher.update(video_id.as_ref().as_bytes());
-				bytes_to_hex_string(&hasher.finalize()[..])
-			};
-			request = self.http.get(format!(
-				"{}{}/{}",
-				&self.base_url,
-				API_ENDPOINT,
-				&video_id_hash[0..self.hash_prefix_length as usize]
-			));
+			from_json_str::<Vec<Value>>(response.as_str())?
+				.into_iter()
+				.find(|hash_match| {
+					hash_match.get("videoID").and_then(Value::as_str) == Some(video_id)
+				})
+				.map(|mut hash_match| hash_match["segments"].take())
+				.ok_or(SponsorBlockError::VideoNotFound)
 		}
+	}
+
+	/// Fetches the segments for a given video ID, cleaned up for immediate use
+	/// in a player.
+	///
+	/// Overlapping segments of the same category and action type are merged
+	/// using [`MergeStrategy::PreferLocked`], and the result is sorted by start
+	/// time. See [`merge_overlapping`] for more information. If you need every
+	/// segment the API returns, use [`fetch_segments`] instead.
+	///
+	/// This function *does not* return additional segment info.
+	///
+	/// # Errors
+	/// See the Errors section of the [base version of this
+	/// function](Self::fetch_segments).
+	///
+	/// [`merge_overlapping`]: crate::merge_overlapping
+	/// [`fetch_segments`]: Self::fetch_segments
+	pub async fn fetch_clean_segments<V>(
+		&self,
+		video_id: V,
+		accepted_categories: AcceptedCategories,
+		accepted_actions: AcceptedActions,
+	) -> Result<Vec<Segment>>
+	where
+		V: AsRef<str>,
+	{
+		Ok(merge_overlapping(
+			self.fetch_segments(video_id, accepted_categories, accepted_actions)
+				.await?,
+			MergeStrategy::PreferLocked,
+		))
+	}
 
-		request = request
-			.query(&[(
-				"categories",
-				convert_category_bitflags_to_url(accepted_categories),
-			)])
-			.query(&[(
-				"actionTypes",
-				convert_action_bitflags_to_url(accepted_actions),
-			)])
-			.query(&[("service", &self.service)]);
-		if !required_segments.is_empty() {
-			request = request.query(&[("requiredSegments", to_url_array(required_segments))]);
+	/// Fetches the segments for a given video ID that a player would skip or
+	/// mute, sorted by start time.
+	///
+	/// This fetches every category, but only the [`Skip`] and [`Mute`] action
+	/// types. It's a shortcut for the most common way of calling
+	/// [`fetch_segments`].
+	///
+	/// This function *does not* return additional segment info.
+	///
+	/// # Errors
+	/// See the Errors section of the [base version of this
+	/// function](Self::fetch_segments).
+	///
+	/// [`Skip`]: crate::Action::Skip
+	/// [`Mute`]: crate::Action::Mute
+	/// [`fetch_segments`]: Self::fetch_segments
+	pub async fn fetch_skip_list<V>(&self, video_id: V) -> Result<Vec<Segment>>
+	where
+		V: AsRef<str>,
+	{
+		let mut segments = self
+			.fetch_segments(
+				video_id,
+				AcceptedCategories::all(),
+				AcceptedActions::SKIP | AcceptedActions::MUTE,
+			)
+			.await?;
+		segments.sort_by(Segment::cmp_by_start_time);
+
+		Ok(segments)
+	}
+
+	/// Fetches the segments for a given video ID that a user's preferences
+	/// care about, along with what a player should do with each one.
+	///
+	/// Only the categories that aren't [`Disabled`] are fetched, and each
+	/// segment is paired with its behaviour as decided by [`should_skip`]. The
+	/// result is sorted by start time. If every category is disabled, no
+	/// request is sent and an empty list is returned.
+	///
+	/// This function *does not* return additional segment info.
+	///
+	/// # Errors
+	/// See the Errors section of the [base version of this
+	/// function](Self::fetch_segments).
+	///
+	/// [`Disabled`]: crate::CategoryPreference::Disabled
+	/// [`should_skip`]: crate::should_skip
+	pub async fn fetch_segments_for_prefs<V>(
+		&self,
+		video_id: V,
+		prefs: &CategoryPrefs,
+	) -> Result<Vec<(Segment, SkipBehavior)>>
+	where
+		V: AsRef<str>,
+	{
+		let accepted_categories = prefs.accepted_categories();
+		if accepted_categories.is_empty() {
+			return Ok(Vec::new());
+		}
+
+		let mut segments = self
+			.fetch_segments(video_id, accepted_categories, AcceptedActions::all())
+			.await?;
+		segments.sort_by(Segment::cmp_by_start_time);
+
+		Ok(segments
+			.into_iter()
+			.map(|segment| {
+				let behavior = should_skip(&segment, prefs);
+				(segment, behavior)
+			})
+			.collect())
+	}
+
+	/// Fetches the segments for a given video ID, along with the validator
+	/// needed to check later whether they've changed.
+	///
+	/// This is for callers that keep their own cache of segments. Pass the
+	/// validator to [`fetch_segments_if_changed`] to only download the
+	/// segments again if they've changed. For a cache built into the client
+	/// instead, see [`ClientBuilder::conditional_segment_requests`].
+	///
+	/// The validator comes from the `ETag` and `Last-Modified` headers of the
+	/// response, and is empty if the server didn't provide them.
+	///
+	/// This function *does not* return additional segment info.
+	///
+	/// # Errors
+	/// See the Errors section of the [base version of this
+	/// function](Self::fetch_segments).
+	///
+	/// [`fetch_segments_if_changed`]: Self::fetch_segments_if_changed
+	/// [`ClientBuilder::conditional_segment_requests`]: crate::ClientBuilder::conditional_segment_requests
+	pub async fn fetch_segments_versioned<V>(
+		&self,
+		video_id: V,
+		accepted_categories: AcceptedCategories,
+		accepted_actions: AcceptedActions,
+	) -> Result<(Vec<Segment>, CacheValidator)>
+	where
+		V: AsRef<str>,
+	{
+		self.fetch_segments_if_changed(
+			video_id,
+			accepted_categories,
+			accepted_actions,
+			&CacheValidator::default(),
+		)
+		.await
+		.map(Option::unwrap_or_default)
+	}
+
+	/// Fetches the segments for a given video ID if they've changed since the
+	/// response that `validator` came from.
+	///
+	/// Returns [`None`] if the server says nothing has changed, meaning the
+	/// segments from that response are still current. Otherwise, returns the
+	/// new segments and their validator. See [`fetch_segments_versioned`] for
+	/// more information.
+	///
+	/// If the validator is empty, the segments are always fetched.
+	///
+	/// This function *does not* return additional segment info.
+	///
+	/// # Errors
+	/// See the Errors section of the [base version of this
+	/// function](Self::fetch_segments).
+	///
+	/// [`fetch_segments_versioned`]: Self::fetch_segments_versioned
+	pub async fn fetch_segments_if_changed<V>(
+		&self,
+		video_id: V,
+		accepted_categories: AcceptedCategories,
+		accepted_actions: AcceptedActions,
+		validator: &CacheValidator,
+	) -> Result<Option<(Vec<Segment>, CacheValidator)>>
+	where
+		V: AsRef<str>,
+	{
+		let video_id = self.resolve_video_id(video_id.as_ref());
+
+		self.fetch_segments_conditionally(
+			&video_id,
+			accepted_categories,
+			accepted_actions,
+			validator.clone(),
+		)
+		.await
+	}
+
+	/// Fetches the segments for a given video ID, accepting different action
+	/// types for each category.
+	///
+	/// This is useful for mirroring per-category configuration, such as only
+	/// accepting full-video labels for one category while accepting every
+	/// action type for the others. Categories missing from `category_actions`,
+	/// or mapped to [`AcceptedActions::NONE`], aren't fetched.
+	///
+	/// The API only accepts a single set of action types for every category,
+	/// so this sends one request for all the categories and action types
+	/// involved, then drops the segments whose action type wasn't accepted for
+	/// their category. It can't change the action a segment was submitted
+	/// with - to treat a category's segments as mutes instead of skips, see
+	/// [`fetch_segments_for_prefs`].
+	///
+	/// This function *does not* return additional segment info.
+	///
+	/// # Errors
+	/// See the Errors section of the [base version of this
+	/// function](Self::fetch_segments).
+	///
+	/// [`fetch_segments_for_prefs`]: Self::fetch_segments_for_prefs
+	pub async fn fetch_segments_with_category_actions<V>(
+		&self,
+		video_id: V,
+		category_actions: &HashMap<Category, AcceptedActions>,
+	) -> Result<Vec<Segment>>
+	where
+		V: AsRef<str>,
+	{
+		let (accepted_categories, accepted_actions) = category_actions
+			.iter()
+			.filter(|(_, actions)| !actions.is_empty())
+			.fold(
+				(AcceptedCategories::NONE, AcceptedActions::NONE),
+				|(categories, actions), (category, category_actions)| {
+					(
+						categories | category.as_accepted(),
+						actions | *category_actions,
+					)
+				},
+			);
+
+		let mut segments = self
+			.fetch_segments(video_id, accepted_categories, accepted_actions)
+			.await?;
+		segments.retain(|segment| {
+			category_actions
+				.get(&segment.category)
+				.is_some_and(|actions| actions.contains(segment.action_kind().as_accepted()))
+		});
+
+		Ok(segments)
+	}
+
+	/// Fetches the segments for a given video ID, returning both every segment
+	/// and the cleaned-up list from a single request.
+	///
+	/// This is useful for letting users switch between seeing all submissions
+	/// and only the best ones. See [`fetch_clean_segments`] for how the list is
+	/// cleaned up.
+	///
+	/// This function *does not* return additional segment info.
+	///
+	/// # Errors
+	/// See the Errors section of the [base version of this
+	/// function](Self::fetch_segments).
+	///
+	/// [`fetch_clean_segments`]: Self::fetch_clean_segments
+	pub async fn fetch_segments_full<V>(
+		&self,
+		video_id: V,
+		accepted_categories: AcceptedCategories,
+		accepted_actions: AcceptedActions,
+	) -> Result<SegmentSet>
+	where
+		V: AsRef<str>,
+	{
+		let all = self
+			.fetch_segments(video_id, accepted_categories, accepted_actions)
+			.await?;
+		let best = merge_overlapping(all.clone(), MergeStrategy::PreferLocked);
+
+		Ok(SegmentSet { all, best })
+	}
+
+	/// Fetches the segments for a given video ID from multiple services at
+	/// once.
+	///
+	/// This is useful when the same content is available on several services.
+	/// The requests are issued concurrently, with all other configuration
+	/// taken from the client.
+	///
+	/// This function *does not* return additional segment info.
+	///
+	/// The results are keyed by service. Failures are recorded per service
+	/// instead of failing the whole call - see the Errors section of
+	/// [`fetch_segments`] for what each one may contain.
+	///
+	/// [`fetch_segments`]: Self::fetch_segments
+	pub async fn fetch_segments_multi_service<V, S>(
+		&self,
+		video_id: V,
+		services: &[S],
+		accepted_categories: AcceptedCategories,
+		accepted_actions: AcceptedActions,
+	) -> HashMap<String, Result<Vec<Segment>>>
+	where
+		V: AsRef<str>,
+		S: AsRef<str>,
+	{
+		let video_id = self.resolve_video_id(video_id.as_ref());
+		let video_id = video_id.as_ref();
+		join_all(services.iter().map(|service| async move {
+			(
+				service.as_ref().to_owned(),
+				self.fetch_segments_for_service(
+					video_id,
+					accepted_categories,
+					accepted_actions,
+					None,
+					service.as_ref(),
+				)
+				.await,
+			)
+		}))
+		.await
+		.into_iter()
+		.collect()
+	}
+
+	/// Fetches the segments for a list of video IDs, such as the videos in a
+	/// playlist.
+	///
+	/// Under the `private_searches` feature, videos that share a hash prefix
+	/// are fetched together in a single request. The requests are issued
+	/// concurrently.
+	///
+	/// This function *does not* return additional segment info.
+	///
+	/// The results are keyed by video ID. Failures are recorded per video
+	/// instead of failing the whole batch - see the Errors section of
+	/// [`fetch_segments`] for what each one may contain.
+	///
+	/// [`fetch_segments`]: Self::fetch_segments
+	pub async fn fetch_segments_for_playlist<V>(
+		&self,
+		video_ids: &[V],
+		accepted_categories: AcceptedCategories,
+		accepted_actions: AcceptedActions,
+	) -> HashMap<String, Result<Vec<Segment>>>
+	where
+		V: AsRef<str>,
+	{
+		self.fetch_segments_for_playlist_with_progress(
+			video_ids,
+			accepted_categories,
+			accepted_actions,
+			|_, _| {},
+		)
+		.await
+	}
+
+	/// Fetches the segments for a list of video IDs, reporting progress as
+	/// videos complete.
+	///
+	/// `progress` is called with the number of videos completed so far and the
+	/// total number of videos, with duplicate video IDs only counted once.
+	/// Under the `private_searches` feature, videos fetched together complete
+	/// together.
+	///
+	/// To cancel the batch early, drop the returned future. Requests that are
+	/// already in flight are abandoned, and no more are sent.
+	///
+	/// See [`fetch_segments_for_playlist`] for more information.
+	///
+	/// [`fetch_segments_for_playlist`]: Self::fetch_segments_for_playlist
+	pub async fn fetch_segments_for_playlist_with_progress<V, P>(
+		&self,
+		video_ids: &[V],
+		accepted_categories: AcceptedCategories,
+		accepted_actions: AcceptedActions,
+		progress: P,
+	) -> HashMap<String, Result<Vec<Segment>>>
+	where
+		V: AsRef<str>,
+		P: Fn(usize, usize),
+	{
+		let resolved_video_ids = video_ids
+			.iter()
+			.map(|video_id| self.resolve_video_id(video_id.as_ref()))
+			.collect::<Vec<_>>();
+		let mut unique_video_ids: Vec<&str> = Vec::with_capacity(video_ids.len());
+		for video_id in &resolved_video_ids {
+			if !unique_video_ids.contains(&video_id.as_ref()) {
+				unique_video_ids.push(video_id.as_ref());
+			}
+		}
+		let total = unique_video_ids.len();
+		let completed = AtomicUsize::new(0);
+		let report_completed = |count: usize| {
+			progress(
+				completed.fetch_add(count, AtomicOrdering::Relaxed) + count,
+				total,
+			);
+		};
+
+		// Errors can't be cloned, so the check is repeated to get one for each
+		// video
+		if check_filter(accepted_categories, accepted_actions).is_err() {
+			report_completed(total);
+			return unique_video_ids
+				.into_iter()
+				.map(|video_id| {
+					(
+						video_id.to_owned(),
+						check_filter(accepted_categories, accepted_actions).map(|()| Vec::new()),
+					)
+				})
+				.collect();
 		}
-		let response = get_response_text(request.send().await?).await?;
 
-		// Deserialize the response and parse it into the output
-		let mut video_segments;
 		#[cfg(not(feature = "private_searches"))]
 		{
-			video_segments = from_json_str::<Vec<RawSegment>>(response.as_str())?;
+			join_all(unique_video_ids.into_iter().map(|video_id| async move {
+				let video_segments = self
+					.fetch_segments_for_service(
+						video_id,
+						accepted_categories,
+						accepted_actions,
+						None,
+						&self.service,
+					)
+					.await;
+				report_completed(1);
+				(video_id.to_owned(), video_segments)
+			}))
+			.await
+			.into_iter()
+			.collect()
 		}
 		#[cfg(feature = "private_searches")]
 		{
-			let mut found_match = false;
-			video_segments = Vec::new();
-			for hash_match in from_json_str::<Vec<RawHashMatch>>(response.as_str())?.drain(..) {
-				if hash_match.video_id == video_id.as_ref() {
-					video_segments = hash_match.segments;
-					found_match = true;
-					break;
+			// Group the videos by hash prefix
+			let mut prefix_groups: HashMap<String, Vec<&str>> = HashMap::new();
+			let mut prefix_errors = Vec::new();
+			for video_id in unique_video_ids {
+				let video_id_hash = hash_video_id(video_id);
+				match hash_prefix(&video_id_hash, self.hash_prefix_length) {
+					Ok(hash_prefix) => prefix_groups
+						.entry(hash_prefix.to_owned())
+						.or_default()
+						.push(video_id),
+					Err(error) => prefix_errors.push((video_id.to_owned(), Err(error))),
 				}
 			}
-			if !found_match {
-				return Err(SponsorBlockError::NoMatchingVideoHash);
+			if !prefix_errors.is_empty() {
+				report_completed(prefix_errors.len());
 			}
+
+			// Fetch each group, then split the results back up into the videos
+			let group_results =
+				join_all(prefix_groups.iter().map(|(hash_prefix, group)| async move {
+					let hash_matches = self
+						.fetch_hash_matches(
+							hash_prefix,
+							accepted_categories,
+							accepted_actions,
+							None,
+							&self.service,
+						)
+						.await;
+					let mut results = Vec::with_capacity(group.len());
+					match hash_matches {
+						Ok(mut hash_matches) => {
+							for &video_id in group {
+								let video_segments = hash_matches
+									.iter()
+									.position(|hash_match| hash_match.video_id == video_id)
+									.ok_or(SponsorBlockError::VideoNotFound)
+									.and_then(|index| {
+										self.convert_raw_segments(
+											hash_matches.swap_remove(index).segments,
+											false,
+										)
+									});
+								results.push((video_id.to_owned(), video_segments));
+							}
+						}
+						Err(SponsorBlockError::VideoNotFound) => {
+							for &video_id in group {
+								results.push((
+									video_id.to_owned(),
+									Err(SponsorBlockError::VideoNotFound),
+								));
+							}
+						}
+						// Other errors can't be shared between videos, so fall back to fetching
+						// each one on its own to attribute them properly
+						Err(error) if group.len() == 1 => {
+							results.push((group[0].to_owned(), Err(error)));
+						}
+						Err(_) => {
+							for &video_id in group {
+								results.push((
+									video_id.to_owned(),
+									self.fetch_segments_for_service(
+										video_id,
+										accepted_categories,
+										accepted_actions,
+										None,
+										&self.service,
+									)
+									.await,
+								));
+							}
+						}
+					}
+					report_completed(results.len());
+					results
+				}))
+				.await;
+
+			group_results
+				.into_iter()
+				.flatten()
+				.chain(prefix_errors)
+				.collect()
+		}
+	}
+
+	/// Runs a video ID through [`parse_video_id`] if
+	/// [`ClientBuilder::auto_parse_video_ids`] is enabled, falling back to the
+	/// input as-is.
+	///
+	/// [`ClientBuilder::auto_parse_video_ids`]: crate::ClientBuilder::auto_parse_video_ids
+	fn resolve_video_id<'v>(&self, video_id: &'v str) -> Cow<'v, str> {
+		match self
+			.auto_parse_video_ids
+			.then(|| parse_video_id(video_id))
+			.flatten()
+		{
+			Some(parsed_video_id) => Cow::Owned(parsed_video_id),
+			None => Cow::Borrowed(video_id),
 		}
+	}
+
+	/// Fetches the segments for a given video ID, using the provided service.
+	async fn fetch_segments_for_service(
+		&self,
+		video_id: &str,
+		accepted_categories: AcceptedCategories,
+		accepted_actions: AcceptedActions,
+		required_segments: Option<&str>,
+		service: &str,
+	) -> Result<Vec<Segment>> {
+		check_filter(accepted_categories, accepted_actions)?;
+
+		let request = self.video_segments_request(
+			video_id,
+			accepted_categories,
+			accepted_actions,
+			required_segments,
+			service,
+		)?;
+		let response = self
+			.send_segments_request(request)
+			.await
+			.map_err(not_found_as_video_not_found)?;
+
+		self.parse_video_segments(video_id, response.as_str())
+	}
+
+	/// Fetches the segments for a given video ID, revalidating the response
+	/// that `validator` came from if there is one.
+	///
+	/// Returns [`None`] if the server says the response hasn't changed.
+	async fn fetch_segments_conditionally(
+		&self,
+		video_id: &str,
+		accepted_categories: AcceptedCategories,
+		accepted_actions: AcceptedActions,
+		validator: CacheValidator,
+	) -> Result<Option<(Vec<Segment>, CacheValidator)>> {
+		check_filter(accepted_categories, accepted_actions)?;
+
+		let request = self.video_segments_request(
+			video_id,
+			accepted_categories,
+			accepted_actions,
+			None,
+			self.service.as_str(),
+		)?;
+		let cache = ResponseCache::seeded(validator);
+		let response = self
+			.send_cached_request(request, Some(&cache))
+			.await
+			.map_err(not_found_as_video_not_found)?;
+
+		// The seeded cache has no text to give back if nothing changed, and the
+		// API never responds to a segment request with nothing
+		if response.is_empty() {
+			return Ok(None);
+		}
+
+		Ok(Some((
+			self.parse_video_segments(video_id, response.as_str())?,
+			cache.validator(),
+		)))
+	}
+
+	/// Builds a request for the segments of a single video.
+	///
+	/// Under the `private_searches` feature, this requests the segments of
+	/// every video that matches the video ID's hash prefix.
+	fn video_segments_request(
+		&self,
+		video_id: &str,
+		accepted_categories: AcceptedCategories,
+		accepted_actions: AcceptedActions,
+		required_segments: Option<&str>,
+		service: &str,
+	) -> Result<RequestBuilder> {
+		#[cfg(not(feature = "private_searches"))]
+		let lookup = video_id;
+		#[cfg(feature = "private_searches")]
+		let video_id_hash = hash_video_id(video_id);
+		#[cfg(feature = "private_searches")]
+		let lookup = hash_prefix(&video_id_hash, self.hash_prefix_length)?;
+
+		Ok(self.skip_segments_request(
+			lookup,
+			accepted_categories,
+			accepted_actions,
+			required_segments,
+			service,
+		))
+	}
+
+	/// Parses the response to a request built by [`video_segments_request`],
+	/// finding the segments for the video.
+	///
+	/// [`video_segments_request`]: Self::video_segments_request
+	#[cfg_attr(not(feature = "private_searches"), allow(unused_variables))]
+	fn parse_video_segments(&self, video_id: &str, response: &str) -> Result<Vec<Segment>> {
+		#[cfg(not(feature = "private_searches"))]
+		let video_segments = from_json_str::<Vec<RawSegment>>(response)?;
+		#[cfg(feature = "private_searches")]
+		let video_segments = from_json_str::<Vec<RawHashMatch>>(response)?
+			.drain(..)
+			.find(|hash_match| hash_match.video_id == video_id)
+			.ok_or(SponsorBlockError::VideoNotFound)?
+			.segments;
+
+		self.convert_raw_segments(video_segments, false)
+	}
+
+	/// Fetches all the videos and their segments that match a hash prefix.
+	#[cfg(feature = "private_searches")]
+	async fn fetch_hash_matches(
+		&self,
+		hash_prefix: &str,
+		accepted_categories: AcceptedCategories,
+		accepted_actions: AcceptedActions,
+		required_segments: Option<&str>,
+		service: &str,
+	) -> Result<Vec<RawHashMatch>> {
+		let request = self.skip_segments_request(
+			hash_prefix,
+			accepted_categories,
+			accepted_actions,
+			required_segments,
+			service,
+		);
+		let response = self
+			.send_segments_request(request)
+			.await
+			.map_err(not_found_as_video_not_found)?;
+
+		from_json_str::<Vec<RawHashMatch>>(response.as_str())
+	}
+
+	/// Builds a request for segments from the `skipSegments` endpoint.
+	///
+	/// `lookup` is the video ID, or under the `private_searches` feature, the
+	/// hash prefix to look up.
+	fn skip_segments_request(
+		&self,
+		lookup: &str,
+		accepted_categories: AcceptedCategories,
+		accepted_actions: AcceptedActions,
+		required_segments: Option<&str>,
+		service: &str,
+	) -> RequestBuilder {
+		#[cfg(not(feature = "private_searches"))]
+		let request = self
+			.http
+			.get(format!("{}{}", &self.api_url, SKIP_SEGMENTS_API_ENDPOINT))
+			.query(&[("videoID", lookup)]);
+		#[cfg(feature = "private_searches")]
+		let request = self.http.get(format!(
+			"{}{}/{}",
+			&self.api_url, SKIP_SEGMENTS_API_ENDPOINT, lookup
+		));
 
-		video_segments
+		add_segment_query(
+			Self::with_timeout(request, self.segment_timeout),
+			accepted_categories,
+			accepted_actions,
+			required_segments,
+			service,
+		)
+	}
+
+	/// Sends a request built by [`skip_segments_request`], revalidating the
+	/// response to the previous identical request if conditional segment
+	/// requests are enabled.
+	///
+	/// [`skip_segments_request`]: Self::skip_segments_request
+	async fn send_segments_request(&self, request: RequestBuilder) -> Result<String> {
+		let Some(segment_caches) = &self.segment_caches else {
+			return self.send_request(request).await;
+		};
+
+		let request = request.build()?;
+		let cache = segment_caches.get(request.url().as_str());
+		self.send_cached_request(
+			RequestBuilder::from_parts(self.http.clone(), request),
+			Some(&cache),
+		)
+		.await
+	}
+
+	/// Converts raw segments into the proper rusty [`Segment`] type, according
+	/// to the client configuration.
+	fn convert_raw_segments(
+		&self,
+		mut raw_segments: Vec<RawSegment>,
+		additional_info: bool,
+	) -> Result<Vec<Segment>> {
+		raw_segments
 			.drain(..)
-			.map(|s| s.convert_to_segment(false))
+			.filter_map(|s| {
+				s.convert_to_segment(
+					additional_info,
+					self.skip_unknown_segments,
+					self.rewrite_highlight_action,
+				)
+				.transpose()
+			})
 			.collect()
 	}
 
@@ -259,46 +1266,392 @@ impl Client {
 	/// the error type definitions for explanations of when they might be
 	/// encountered.
 	///
+	/// The only error type among them you may want to handle differently is
+	/// [`SegmentNotFound`], as it indicates that no segment could be found in
+	/// the database with the provided UUID.
+	///
+	/// If the UUID obviously isn't a segment UUID, no request is sent and
+	/// [`BadData`] is returned. See [`validate_segment_uuid`] for what's
+	/// accepted.
+	///
 	/// [`SponsorBlockError`]: crate::SponsorBlockError
+	/// [`SegmentNotFound`]: crate::SponsorBlockError::SegmentNotFound
+	/// [`BadData`]: crate::SponsorBlockError::BadData
+	/// [`validate_segment_uuid`]: crate::validate_segment_uuid
 	pub async fn fetch_segment_info<S>(&self, segment_uuid: S) -> Result<Segment>
 	where
 		S: AsRef<str>,
 	{
-		Ok(self
-			.fetch_segment_info_multiple(&[segment_uuid])
-			.await?
-			.pop()
-			.ok_or_else(|| SponsorBlockError::BadData("no segments found".to_owned()))?)
+		let segment_uuid = segment_uuid.as_ref();
+		let not_found = || SponsorBlockError::SegmentNotFound(segment_uuid.to_owned());
+
+		// The API answers with a client error when it doesn't know the UUID
+		match self.fetch_segment_info_multiple(&[segment_uuid]).await {
+			Ok(mut segments) => segments.pop().ok_or_else(not_found),
+			Err(SponsorBlockError::HttpClient(400 | 404)) => Err(not_found()),
+			Err(error) => Err(error),
+		}
 	}
 
 	/// Fetches complete info for segments.
 	///
 	/// This function *does* return additional segment info.
 	///
+	/// The returned segments are in the same order as `segment_uuids`. Any
+	/// UUIDs that the server didn't return a segment for are skipped, so the
+	/// output may be shorter than the input. If a UUID is provided more than
+	/// once, its segment is only returned at its first position.
+	///
 	/// # Errors
 	/// Can return pretty much any error type from [`SponsorBlockError`]. See
 	/// the error type definitions for explanations of when they might be
 	/// encountered.
 	///
+	/// If any of the UUIDs obviously isn't a segment UUID, no request is sent
+	/// and [`BadData`] is returned. See [`validate_segment_uuid`] for what's
+	/// accepted.
+	///
 	/// [`SponsorBlockError`]: crate::SponsorBlockError
+	/// [`BadData`]: crate::SponsorBlockError::BadData
+	/// [`validate_segment_uuid`]: crate::validate_segment_uuid
 	pub async fn fetch_segment_info_multiple<S>(&self, segment_uuids: &[S]) -> Result<Vec<Segment>>
 	where
 		S: AsRef<str>,
 	{
-		// Function Constants
-		const API_ENDPOINT: &str = "/segmentInfo";
+		if let Some(invalid) = segment_uuids
+			.iter()
+			.find(|uuid| !validate_segment_uuid(uuid))
+		{
+			return Err(SponsorBlockError::BadData(format!(
+				"{:?} is not a valid segment UUID",
+				invalid.as_ref()
+			)));
+		}
 
 		// Build the request and send it
-		let request = self
-			.http
-			.get(format!("{}{}", &self.base_url, API_ENDPOINT))
-			.query(&[("UUIDs", to_url_array(segment_uuids))]);
-		let response = get_response_text(request.send().await?).await?;
+		let request = Self::with_timeout(
+			self.http
+				.get(format!("{}{}", &self.api_url, SEGMENT_INFO_API_ENDPOINT))
+				.query(&[("UUIDs", to_url_array(segment_uuids))]),
+			self.segment_timeout,
+		);
+		let response = self.send_request(request).await?;
 
 		// Deserialize the response and parse it into the output
-		from_json_str::<Vec<RawSegment>>(response.as_str())?
+		let mut segments_by_uuid = self
+			.convert_raw_segments(from_json_str::<Vec<RawSegment>>(response.as_str())?, true)?
 			.drain(..)
-			.map(|s| s.convert_to_segment(true))
-			.collect()
+			.map(|segment| (segment.uuid.clone(), segment))
+			.collect::<HashMap<_, _>>();
+
+		// Restore the order of the input, since the server doesn't guarantee it
+		Ok(segment_uuids
+			.iter()
+			.filter_map(|uuid| segments_by_uuid.remove(uuid.as_ref()))
+			.collect())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	// Uses
+	use super::{check_filter, RawSegment};
+	#[cfg(feature = "private_searches")]
+	use super::{hash_prefix, hash_video_id};
+	#[cfg(feature = "mock")]
+	use crate::StatusCode;
+	use crate::{
+		error::Result,
+		util::from_json_str,
+		AcceptedActions,
+		AcceptedCategories,
+		Action,
+		Client,
+		Segment,
+		SponsorBlockError,
+	};
+
+	/// Parses a segment the same way as one from the API.
+	fn parse_segment(json: &str) -> Result<Segment> {
+		from_json_str::<RawSegment>(json)?
+			.convert_to_segment(true, false, true)
+			.map(|segment| segment.expect("unknown segments aren't skipped"))
+	}
+
+	#[test]
+	fn parse_segment_with_missing_fields() {
+		let segment = parse_segment(
+			r#"{"category": "sponsor", "actionType": "skip", "segment": [1.5, 2], "UUID": "a"}"#,
+		)
+		.unwrap();
+		assert_eq!(segment.action, Action::Skip(1.5, 2.0));
+		assert!(!segment.locked);
+		assert_eq!(segment.votes, 0);
+		assert_eq!(segment.video_duration_on_submission, None);
+	}
+
+	#[test]
+	fn parse_segment_with_separate_string_times() {
+		let segment = parse_segment(
+			r#"{"category": "sponsor", "actionType": "mute", "startTime": "1.5", "endTime": 2, "UUID": "a"}"#,
+		)
+		.unwrap();
+		assert_eq!(segment.action, Action::Mute(1.5, 2.0));
+	}
+
+	#[test]
+	fn parse_segment_without_times() {
+		let full_video =
+			parse_segment(r#"{"category": "exclusive_access", "actionType": "full", "UUID": "a"}"#)
+				.unwrap();
+		assert_eq!(full_video.action, Action::FullVideo);
+
+		let full_video = parse_segment(
+			r#"{"category": "exclusive_access", "actionType": "full", "segment": null, "UUID": "a"}"#,
+		)
+		.unwrap();
+		assert_eq!(full_video.action, Action::FullVideo);
+
+		assert!(matches!(
+			parse_segment(r#"{"category": "sponsor", "actionType": "skip", "UUID": "a"}"#),
+			Err(SponsorBlockError::BadData(_))
+		));
+	}
+
+	#[test]
+	fn parse_segment_locked_values() {
+		// (raw value, expected)
+		let cases = [
+			("1", true),
+			("0", false),
+			("true", true),
+			("false", false),
+			("null", false),
+		];
+
+		for (raw, expected) in cases {
+			let segment = parse_segment(&format!(
+				r#"{{"category": "sponsor", "actionType": "skip", "segment": [1, 2], "UUID": "a", "locked": {raw}}}"#
+			))
+			.unwrap();
+			assert_eq!(segment.locked, expected, "{raw}");
+		}
+	}
+
+	#[test]
+	fn parse_segment_video_duration_values() {
+		// (raw value, expected)
+		let cases = [
+			("null", None),
+			("0", None),
+			("0.0", None),
+			("634.5", Some(634.5)),
+		];
+
+		for (raw, expected) in cases {
+			let segment = parse_segment(&format!(
+				r#"{{"category": "sponsor", "actionType": "skip", "segment": [1, 2], "UUID": "a", "videoDuration": {raw}}}"#
+			))
+			.unwrap();
+			assert_eq!(segment.video_duration_on_submission, expected, "{raw}");
+		}
+	}
+
+	#[test]
+	fn check_filter_accepts_non_empty_filters() {
+		assert!(check_filter(AcceptedCategories::all(), AcceptedActions::all()).is_ok());
+		assert!(check_filter(AcceptedCategories::SPONSOR, AcceptedActions::SKIP).is_ok());
+	}
+
+	#[test]
+	fn check_filter_rejects_empty_categories() {
+		assert!(matches!(
+			check_filter(AcceptedCategories::NONE, AcceptedActions::all()),
+			Err(SponsorBlockError::BadData(message)) if message == "no categories requested"
+		));
+	}
+
+	#[test]
+	fn check_filter_rejects_empty_actions() {
+		assert!(matches!(
+			check_filter(AcceptedCategories::all(), AcceptedActions::NONE),
+			Err(SponsorBlockError::BadData(message)) if message == "no action types requested"
+		));
+	}
+
+	#[cfg(feature = "private_searches")]
+	#[test]
+	fn hash_prefix_accepts_allowed_lengths() {
+		let video_id_hash = hash_video_id("dQw4w9WgXcQ");
+		assert_eq!(hash_prefix(&video_id_hash, 4).unwrap(), &video_id_hash[..4]);
+		assert_eq!(
+			hash_prefix(&video_id_hash, 32).unwrap(),
+			&video_id_hash[..32]
+		);
+	}
+
+	#[cfg(feature = "private_searches")]
+	#[test]
+	fn hash_prefix_rejects_disallowed_lengths() {
+		let video_id_hash = hash_video_id("dQw4w9WgXcQ");
+		for hash_prefix_length in [0, 3, 33, u8::MAX] {
+			assert!(matches!(
+				hash_prefix(&video_id_hash, hash_prefix_length),
+				Err(SponsorBlockError::InvalidHashPrefixLength(length)) if length == hash_prefix_length
+			));
+		}
+	}
+
+	#[cfg(feature = "private_searches")]
+	#[test]
+	fn hash_prefix_rejects_short_hashes() {
+		assert!(matches!(
+			hash_prefix("abcd", 5),
+			Err(SponsorBlockError::BadData(_))
+		));
+	}
+
+	#[cfg(feature = "mock")]
+	const UUID_A: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa7";
+	#[cfg(feature = "mock")]
+	const UUID_B: &str = "bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb7";
+	#[cfg(feature = "mock")]
+	const UUID_C: &str = "cccccccccccccccccccccccccccccccccccccccccccccccccccccccccccccccc7";
+
+	#[cfg(feature = "mock")]
+	fn raw_segment(uuid: &str) -> String {
+		format!(
+			r#"{{"category": "sponsor", "actionType": "skip", "segment": [1.0, 2.0], "UUID": "{uuid}", "locked": 0, "votes": 0, "videoDuration": 0, "videoID": "dQw4w9WgXcQ"}}"#
+		)
+	}
+
+	#[cfg(feature = "mock")]
+	fn client_responding_with(status: StatusCode, body: String) -> Client {
+		Client::with_responder("test", move |_: &str| (status, body.clone()))
+	}
+
+	#[cfg(feature = "mock")]
+	#[tokio::test]
+	async fn fetch_segment_info_multiple_restores_input_order() {
+		// The server answers out of order, and doesn't know about `UUID_C`
+		let client = client_responding_with(
+			200,
+			format!("[{},{}]", raw_segment(UUID_B), raw_segment(UUID_A)),
+		);
+
+		let segments = client
+			.fetch_segment_info_multiple(&[UUID_A, UUID_C, UUID_B, UUID_A])
+			.await
+			.unwrap();
+		let uuids = segments
+			.iter()
+			.map(|segment| segment.uuid.as_str())
+			.collect::<Vec<_>>();
+		assert_eq!(uuids, [UUID_A, UUID_B]);
+	}
+
+	#[cfg(feature = "mock")]
+	#[tokio::test]
+	async fn fetch_segment_info_multiple_rejects_invalid_uuids() {
+		let client = client_responding_with(200, "[]".to_owned());
+
+		let error = client
+			.fetch_segment_info_multiple(&[UUID_A, "not-a-uuid"])
+			.await
+			.unwrap_err();
+		assert!(matches!(error, SponsorBlockError::BadData(_)));
+	}
+
+	#[cfg(feature = "mock")]
+	#[tokio::test]
+	async fn fetch_segments_with_empty_filter_sends_no_request() {
+		let client = Client::with_responder("test", |url: &str| -> (StatusCode, String) {
+			panic!("unexpected request to {url}");
+		});
+
+		let error = client
+			.fetch_segments(
+				"dQw4w9WgXcQ",
+				AcceptedCategories::NONE,
+				AcceptedActions::all(),
+			)
+			.await
+			.unwrap_err();
+		assert!(matches!(error, SponsorBlockError::BadData(_)));
+	}
+
+	#[cfg(feature = "mock")]
+	#[tokio::test]
+	async fn fetch_segment_info_maps_client_errors_to_segment_not_found() {
+		for status in [400, 404] {
+			let client = client_responding_with(status, String::new());
+
+			let error = client.fetch_segment_info(UUID_A).await.unwrap_err();
+			assert!(
+				matches!(&error, SponsorBlockError::SegmentNotFound(uuid) if uuid == UUID_A),
+				"status {status} gave {error:?}"
+			);
+		}
+	}
+
+	#[cfg(feature = "mock")]
+	#[tokio::test]
+	async fn fetch_segment_info_keeps_other_errors() {
+		let client = client_responding_with(500, String::new());
+
+		let error = client.fetch_segment_info(UUID_A).await.unwrap_err();
+		assert!(matches!(error, SponsorBlockError::HttpApi(500)));
+	}
+
+	#[cfg(feature = "mock")]
+	#[tokio::test]
+	async fn fetch_segment_info_returns_the_segment() {
+		let client = client_responding_with(200, format!("[{}]", raw_segment(UUID_A)));
+
+		let segment = client.fetch_segment_info(UUID_A).await.unwrap();
+		assert_eq!(segment.uuid, UUID_A);
+	}
+
+	#[test]
+	fn auto_parse_video_ids_resolves_urls() {
+		let client = Client::builder("test").auto_parse_video_ids(true).build();
+		let url = |video_id: &str| {
+			client
+				.build_segments_url(video_id, AcceptedCategories::all(), AcceptedActions::all())
+				.unwrap()
+		};
+
+		assert_eq!(
+			url("https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=42"),
+			url("dQw4w9WgXcQ")
+		);
+		assert_eq!(url("https://youtu.be/dQw4w9WgXcQ"), url("dQw4w9WgXcQ"));
+	}
+
+	#[cfg(all(feature = "mock", feature = "private_searches"))]
+	#[tokio::test]
+	async fn fetch_segments_for_playlist_keys_by_parsed_video_id() {
+		let client = Client::builder("test")
+			.auto_parse_video_ids(true)
+			.responder(|_: &str| {
+				(
+					200,
+					format!(
+						r#"[{{"videoID": "dQw4w9WgXcQ", "hash": "", "segments": [{}]}}]"#,
+						raw_segment(UUID_A)
+					),
+				)
+			})
+			.build();
+
+		let results = client
+			.fetch_segments_for_playlist(
+				&["https://youtu.be/dQw4w9WgXcQ", "dQw4w9WgXcQ"],
+				AcceptedCategories::all(),
+				AcceptedActions::all(),
+			)
+			.await;
+		assert_eq!(results.len(), 1);
+		assert_eq!(results["dQw4w9WgXcQ"].as_ref().unwrap().len(), 1);
 	}
 }
@@ -0,0 +1,55 @@
+//! The functions for retrieving overall statistics for the service.
+
+// Uses
+use serde::Deserialize;
+
+use crate::{error::Result, util::from_json_str, Client};
+
+/// The results of a total stats request.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, PartialOrd)]
+#[non_exhaustive]
+#[serde(default, rename_all = "camelCase")]
+pub struct TotalStats {
+	/// The number of users who have submitted at least one segment.
+	#[serde(rename = "userCount")]
+	pub contributing_users: u32,
+	/// The approximate number of users of the official browser extension.
+	pub active_users: u32,
+	/// The approximate number of users of the API, including from other
+	/// applications.
+	pub api_users: u32,
+	/// The total number of views on all segments.
+	pub view_count: u64,
+	/// The total number of segments submitted.
+	pub total_submissions: u32,
+	/// The total number of minutes saved for all users.
+	pub minutes_saved: f64,
+}
+
+// Function Constants
+pub(super) const API_ENDPOINT: &str = "/getTotalStats";
+
+// Function Implementation
+impl Client {
+	/// Fetches the overall statistics for the service.
+	///
+	/// # Errors
+	/// Can return pretty much any error type from [`SponsorBlockError`]. See
+	/// the error type definitions for explanations of when they might be
+	/// encountered.
+	///
+	/// [`SponsorBlockError`]: crate::SponsorBlockError
+	pub async fn fetch_total_stats(&self) -> Result<TotalStats> {
+		// Build the request
+		let request = self
+			.http
+			.get(format!("{}{}", &self.api_url, API_ENDPOINT))
+			.query(&[("countContributingUsers", "true")]);
+
+		// Send the request
+		let response = self.send_request(request).await?;
+
+		// Parse the response
+		from_json_str::<TotalStats>(response.as_str())
+	}
+}
@@ -2,9 +2,13 @@
 
 // Uses
 use serde::Deserialize;
-use serde_json::from_str as from_json_str;
+use time::Duration;
 
-use crate::{error::Result, util::get_response_text, Client};
+use crate::{
+	error::Result,
+	util::{from_json_str, to_url_array},
+	Client,
+};
 
 /// The results of a user info request.
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, PartialOrd)]
@@ -54,10 +58,28 @@ impl UserInfo {
 	pub fn total_view_count(&self) -> u32 {
 		self.view_count + self.ignored_view_count
 	}
+
+	/// A convenience function that gets the time this user has saved other
+	/// users as a [`Duration`].
+	/// (`minutes_saved`)
+	///
+	/// Values too large for a [`Duration`] are capped at [`Duration::MAX`] or
+	/// [`Duration::MIN`], and `NaN` is treated as no time at all.
+	#[must_use]
+	pub fn time_saved(&self) -> Duration {
+		Duration::saturating_seconds_f32(self.minutes_saved * 60.0)
+	}
+}
+
+// Function-Specific Deserialization Structs
+#[derive(Debug, Deserialize)]
+struct RawPublicUserId {
+	#[serde(rename = "userID")]
+	public_user_id: String,
 }
 
 // Function Constants
-const API_ENDPOINT: &str = "/userInfo";
+pub(super) const API_ENDPOINT: &str = "/userInfo";
 
 // Function Implementation
 impl Client {
@@ -76,22 +98,17 @@ impl Client {
 		// Build the request
 		let request = self
 			.http
-			.get(format!("{}{}", &self.base_url, API_ENDPOINT))
+			.get(format!("{}{}", &self.api_url, API_ENDPOINT))
 			.query(&[("publicUserID", public_user_id.as_ref())]);
 
 		// Send the request
-		let response = get_response_text(request.send().await?).await?;
+		let response = self.send_request(request).await?;
 
 		// Parse the response
 		let mut result = from_json_str::<UserInfo>(response.as_str())?;
 		// The user name is set to the public user ID if not set. This converts it to a
 		// more idiomatic value transparently.
-		if result
-			.user_name
-			.as_ref()
-			.expect("userName field was not set")
-			.eq(&result.public_user_id)
-		{
+		if result.user_name.as_ref() == Some(&result.public_user_id) {
 			result.user_name = None;
 		}
 		Ok(result)
@@ -112,24 +129,77 @@ impl Client {
 		// Build the request
 		let request = self
 			.http
-			.get(format!("{}{}", &self.base_url, API_ENDPOINT))
+			.get(format!("{}{}", &self.api_url, API_ENDPOINT))
 			.query(&[("userID", local_user_id.as_ref())]);
 
 		// Send the request
-		let response = get_response_text(request.send().await?).await?;
+		let response = self.send_request(request).await?;
 
 		// Parse the response
 		let mut result = from_json_str::<UserInfo>(response.as_str())?;
 		// The user name is set to the public user ID if not set. This converts it to a
 		// more idiomatic value transparently.
-		if result
-			.user_name
-			.as_ref()
-			.expect("userName field was not set")
-			.eq(&result.public_user_id)
-		{
+		if result.user_name.as_ref() == Some(&result.public_user_id) {
 			result.user_name = None;
 		}
 		Ok(result)
 	}
+
+	/// Gets the public user ID corresponding to a local (private) user ID.
+	///
+	/// This only requests the public user ID from the API, so it's lighter than
+	/// [`fetch_user_info_local`] when that's all that's needed.
+	///
+	/// # Errors
+	/// Can return pretty much any error type from [`SponsorBlockError`]. See
+	/// the error type definitions for explanations of when they might be
+	/// encountered.
+	///
+	/// [`fetch_user_info_local`]: Self::fetch_user_info_local
+	/// [`SponsorBlockError`]: crate::SponsorBlockError
+	pub async fn local_to_public_id<U>(&self, local_user_id: U) -> Result<String>
+	where
+		U: AsRef<str>,
+	{
+		// Build the request
+		let request = self
+			.http
+			.get(format!("{}{}", &self.api_url, API_ENDPOINT))
+			.query(&[("userID", local_user_id.as_ref())])
+			.query(&[("values", to_url_array(&["userID"]))]);
+
+		// Send the request
+		let response = self.send_request(request).await?;
+
+		// Parse the response
+		Ok(from_json_str::<RawPublicUserId>(response.as_str())?.public_user_id)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	// Uses
+	use time::Duration;
+
+	use super::UserInfo;
+
+	#[test]
+	fn time_saved_handles_unusual_values() {
+		// (minutes saved, expected duration)
+		let cases = [
+			(1.5, Duration::seconds(90)),
+			(f32::MAX, Duration::MAX),
+			(f32::INFINITY, Duration::MAX),
+			(f32::NEG_INFINITY, Duration::MIN),
+			(f32::NAN, Duration::ZERO),
+		];
+
+		for (minutes_saved, expected) in cases {
+			let user_info = UserInfo {
+				minutes_saved,
+				..UserInfo::default()
+			};
+			assert_eq!(user_info.time_saved(), expected, "{minutes_saved}");
+		}
+	}
 }
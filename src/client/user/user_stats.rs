@@ -1,15 +1,15 @@
 //! The functions for retrieving user statistics.
 
 // Uses
-use std::{collections::HashMap, result::Result as StdResult};
+use std::{collections::HashMap, hash::Hash, result::Result as StdResult};
 
 use serde::{Deserialize, Deserializer};
-use serde_json::from_str as from_json_str;
+use time::Duration;
 
 use crate::{
 	api::{convert_to_action_kind, convert_to_category},
 	error::Result,
-	util::{de::map_hashmap_key_from_str, get_response_text},
+	util::{de::map_hashmap_key_from_str, from_json_str},
 	ActionKind,
 	Category,
 	Client,
@@ -35,6 +35,151 @@ pub struct UserStats {
 	pub action_type_count: HashMap<ActionKind, u32>,
 }
 
+impl UserStats {
+	/// A convenience function that gets the time this user has saved other
+	/// users as a [`Duration`].
+	/// (`overall_stats.minutes_saved`)
+	#[must_use]
+	pub fn time_saved(&self) -> Duration {
+		self.overall_stats.time_saved()
+	}
+
+	/// Gets the `n` categories with the most segments, sorted from most to
+	/// least.
+	///
+	/// Ties are ordered the same way as [`Category`] itself.
+	#[must_use]
+	pub fn top_categories(&self, n: usize) -> Vec<(Category, u32)> {
+		top_counts(&self.category_count, n)
+	}
+
+	/// Gets the `n` action types with the most segments, sorted from most to
+	/// least.
+	///
+	/// Ties are ordered the same way as [`ActionKind`] itself.
+	#[must_use]
+	pub fn top_action_types(&self, n: usize) -> Vec<(ActionKind, u32)> {
+		top_counts(&self.action_type_count, n)
+	}
+
+	/// Gets the categories with their segment counts, sorted in the same order
+	/// as [`Category`] itself.
+	///
+	/// Unlike iterating over [`category_count`] directly, the order is always
+	/// the same, which is useful for display and for snapshot tests.
+	///
+	/// [`category_count`]: Self::category_count
+	#[must_use]
+	pub fn category_count_sorted(&self) -> Vec<(Category, u32)> {
+		sorted_counts(&self.category_count)
+	}
+
+	/// Gets the action types with their segment counts, sorted in the same
+	/// order as [`ActionKind`] itself.
+	///
+	/// See [`category_count_sorted`] for more information.
+	///
+	/// [`category_count_sorted`]: Self::category_count_sorted
+	#[must_use]
+	pub fn action_type_count_sorted(&self) -> Vec<(ActionKind, u32)> {
+		sorted_counts(&self.action_type_count)
+	}
+
+	/// Computes how the stats have changed since a previous snapshot of them,
+	/// such as for showing a user's progress over time.
+	///
+	/// Differences are `self - previous`, so growth is positive.
+	#[must_use]
+	pub fn delta(&self, previous: &Self) -> UserStatsDelta {
+		UserStatsDelta {
+			minutes_saved: self.overall_stats.minutes_saved - previous.overall_stats.minutes_saved,
+			segment_count: i64::from(self.overall_stats.segment_count)
+				- i64::from(previous.overall_stats.segment_count),
+			category_count: count_deltas(&self.category_count, &previous.category_count),
+			action_type_count: count_deltas(&self.action_type_count, &previous.action_type_count),
+		}
+	}
+}
+
+/// The changes in a user's stats between two snapshots, as computed by
+/// [`UserStats::delta`].
+#[derive(Clone, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct UserStatsDelta {
+	/// The change in the number of minutes the user has saved other users.
+	pub minutes_saved: f32,
+	/// The change in the total number of segments submitted.
+	pub segment_count: i64,
+	/// The change in segment counts for each category.
+	///
+	/// Categories whose counts didn't change are left out.
+	pub category_count: HashMap<Category, i64>,
+	/// The change in segment counts for each action type.
+	///
+	/// Action types whose counts didn't change are left out.
+	pub action_type_count: HashMap<ActionKind, i64>,
+}
+
+impl UserStatsDelta {
+	/// A convenience function that gets the change in time the user has saved
+	/// other users as a [`Duration`].
+	/// (`minutes_saved`)
+	///
+	/// Values too large for a [`Duration`] are capped at [`Duration::MAX`] or
+	/// [`Duration::MIN`], and `NaN` is treated as no time at all.
+	#[must_use]
+	pub fn time_saved(&self) -> Duration {
+		Duration::saturating_seconds_f32(self.minutes_saved * 60.0)
+	}
+}
+
+/// Gets the changes in counts between two snapshots, leaving out keys whose
+/// counts didn't change.
+fn count_deltas<K>(current: &HashMap<K, u32>, previous: &HashMap<K, u32>) -> HashMap<K, i64>
+where
+	K: Copy + Eq + Hash,
+{
+	let count = |counts: &HashMap<K, u32>, key| i64::from(counts.get(key).copied().unwrap_or(0));
+	current
+		.keys()
+		.chain(previous.keys())
+		.filter_map(|key| {
+			let delta = count(current, key) - count(previous, key);
+			(delta != 0).then_some((*key, delta))
+		})
+		.collect()
+}
+
+/// Gets all the entries, sorted ascending by key.
+fn sorted_counts<K>(counts: &HashMap<K, u32>) -> Vec<(K, u32)>
+where
+	K: Copy + Ord,
+{
+	let mut entries = counts
+		.iter()
+		.map(|(&key, &count)| (key, count))
+		.collect::<Vec<_>>();
+	entries.sort_unstable_by_key(|&(key, _)| key);
+	entries
+}
+
+/// Gets the `n` entries with the highest counts, sorted descending by count
+/// then ascending by key.
+fn top_counts<K>(counts: &HashMap<K, u32>, n: usize) -> Vec<(K, u32)>
+where
+	K: Copy + Ord,
+{
+	let mut entries = counts
+		.iter()
+		.map(|(&key, &count)| (key, count))
+		.collect::<Vec<_>>();
+	entries.sort_unstable_by(|(key_a, count_a), (key_b, count_b)| {
+		count_b.cmp(count_a).then_with(|| key_a.cmp(key_b))
+	});
+	entries.truncate(n);
+	entries
+}
+
 fn map_category_kinds<'de, D: Deserializer<'de>, O: Deserialize<'de>>(
 	deserializer: D,
 ) -> StdResult<HashMap<Category, O>, D::Error> {
@@ -63,8 +208,21 @@ pub struct OverallStats {
 	pub segment_count: u32,
 }
 
+impl OverallStats {
+	/// A convenience function that gets the time this user has saved other
+	/// users as a [`Duration`].
+	/// (`minutes_saved`)
+	///
+	/// Values too large for a [`Duration`] are capped at [`Duration::MAX`] or
+	/// [`Duration::MIN`], and `NaN` is treated as no time at all.
+	#[must_use]
+	pub fn time_saved(&self) -> Duration {
+		Duration::saturating_seconds_f32(self.minutes_saved * 60.0)
+	}
+}
+
 // Function Constants
-const API_ENDPOINT: &str = "/userStats";
+pub(super) const API_ENDPOINT: &str = "/userStats";
 
 // Function Implementation
 impl Client {
@@ -83,23 +241,18 @@ impl Client {
 		// Build the request
 		let request = self
 			.http
-			.get(format!("{}{}", &self.base_url, API_ENDPOINT))
+			.get(format!("{}{}", &self.api_url, API_ENDPOINT))
 			.query(&[("publicUserID", public_user_id.as_ref())])
 			.query(&[("fetchCategoryStats", true), ("fetchActionTypeStats", true)]);
 
 		// Send the request
-		let response = get_response_text(request.send().await?).await?;
+		let response = self.send_request(request).await?;
 
 		// Parse the response
 		let mut result = from_json_str::<UserStats>(response.as_str())?;
 		// The user name is set to the public user ID if not set. This converts it to a
 		// more idiomatic value transparently.
-		if result
-			.user_name
-			.as_ref()
-			.expect("userName field was not set")
-			.eq(&result.user_id)
-		{
+		if result.user_name.as_ref() == Some(&result.user_id) {
 			result.user_name = None;
 		}
 		Ok(result)
@@ -120,25 +273,54 @@ impl Client {
 		// Build the request
 		let request = self
 			.http
-			.get(format!("{}{}", &self.base_url, API_ENDPOINT))
+			.get(format!("{}{}", &self.api_url, API_ENDPOINT))
 			.query(&[("userID", local_user_id.as_ref())])
 			.query(&[("fetchCategoryStats", true), ("fetchActionTypeStats", true)]);
 
 		// Send the request
-		let response = get_response_text(request.send().await?).await?;
+		let response = self.send_request(request).await?;
 
 		// Parse the response
 		let mut result = from_json_str::<UserStats>(response.as_str())?;
 		// The user name is set to the public user ID if not set. This converts it to a
 		// more idiomatic value transparently.
-		if result
-			.user_name
-			.as_ref()
-			.expect("userName field was not set")
-			.eq(&result.user_id)
-		{
+		if result.user_name.as_ref() == Some(&result.user_id) {
 			result.user_name = None;
 		}
 		Ok(result)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	// Uses
+	use time::Duration;
+
+	use super::{OverallStats, UserStatsDelta};
+
+	#[test]
+	fn time_saved_handles_unusual_values() {
+		// (minutes saved, expected duration)
+		let cases = [
+			(1.5, Duration::seconds(90)),
+			(-1.5, Duration::seconds(-90)),
+			(f32::MAX, Duration::MAX),
+			(f32::MIN, Duration::MIN),
+			(f32::NAN, Duration::ZERO),
+		];
+
+		for (minutes_saved, expected) in cases {
+			let overall_stats = OverallStats {
+				minutes_saved,
+				..OverallStats::default()
+			};
+			assert_eq!(overall_stats.time_saved(), expected, "{minutes_saved}");
+
+			let delta = UserStatsDelta {
+				minutes_saved,
+				..UserStatsDelta::default()
+			};
+			assert_eq!(delta.time_saved(), expected, "{minutes_saved}");
+		}
+	}
+}
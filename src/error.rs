@@ -15,14 +15,17 @@ pub enum SponsorBlockError {
 	/// Contains the status code returned by the server.
 	#[error("internal API error, with status code {0}")]
 	HttpApi(StatusCode),
-	/// A client-side error during communication with the API. A value of 404
-	/// simply means no segments could be found in the database for the video ID
-	/// you requested.
+	/// A client-side error during communication with the API.
+	///
+	/// Segment requests for videos that can't be found in the database return
+	/// [`VideoNotFound`] instead of a 404.
 	///
 	/// If encountering this, it's possible the library version you're using is
 	/// out of date with the API. If that's the case, please open an issue.
 	///
 	/// Contains the status code returned by the server.
+	///
+	/// [`VideoNotFound`]: crate::SponsorBlockError::VideoNotFound
 	#[error("client HTTP error, with status code {0}")]
 	HttpClient(StatusCode),
 	/// An unknown error during communication with the API.
@@ -34,16 +37,45 @@ pub enum SponsorBlockError {
 	/// Contains the internal [`reqwest::Error`].
 	#[error("unable to communicate with the API")]
 	HttpCommunication(#[from] reqwest::Error),
+	/// The response from the API was larger than the configured maximum.
+	///
+	/// Contains the maximum size, in bytes.
+	///
+	/// See [`ClientBuilder::max_response_bytes`] for more information.
+	///
+	/// [`ClientBuilder::max_response_bytes`]: crate::ClientBuilder::max_response_bytes
+	#[error("the API response was larger than the maximum of {0} bytes")]
+	ResponseTooLarge(usize),
+	/// A successful response from the API wasn't JSON, such as an HTML page
+	/// from a proxy or CDN in front of the API.
+	///
+	/// Contains the start of the response text.
+	#[error("received an unexpected non-JSON response from the API: {0}")]
+	UnexpectedResponse(String),
 
 	// Other API Errors
 	/// The API does not have any segments in the database for the requested
 	/// video ID.
 	///
-	/// This should effectively be treated the same way as an
-	/// [`HttpClient(404)`].
+	/// This is returned regardless of whether the `private_searches` feature
+	/// is enabled.
+	#[error("unable to find any segments for the provided video ID")]
+	VideoNotFound,
+	/// The API does not have a segment in the database with the requested
+	/// UUID.
 	///
-	/// [`HttpClient(404)`]: crate::SponsorBlockError::HttpClient
+	/// Contains the UUID that was requested.
+	#[error("unable to find a segment with the UUID {0}")]
+	SegmentNotFound(String),
+	/// The API does not have any segments in the database for the requested
+	/// video ID.
+	///
+	/// This is no longer returned by the library, in favour of
+	/// [`VideoNotFound`].
+	///
+	/// [`VideoNotFound`]: crate::SponsorBlockError::VideoNotFound
 	#[cfg(feature = "private_searches")]
+	#[deprecated(note = "use `VideoNotFound` instead")]
 	#[error("unable to find a matching hash for the provided video ID")]
 	NoMatchingVideoHash,
 
@@ -53,10 +85,24 @@ pub enum SponsorBlockError {
 	/// If encountering this, it's likely the library version you're using is
 	/// out of date with the API. If that's the case, please open an issue.
 	///
-	/// Contains the internal [`serde_json::Error`].
+	/// Contains the internal [`serde_json::Error`]. When the data doesn't match
+	/// the expected structure, its message includes the path to the offending
+	/// value.
 	#[error("unable to deserialize data from the API")]
 	Deserialization(#[from] serde_json::Error),
 
+	// Configuration
+	/// A hash prefix length outside of the allowed range was provided.
+	///
+	/// Contains the hash prefix length that was provided.
+	///
+	/// See [`ClientBuilder::try_hash_prefix_length`] for more information.
+	///
+	/// [`ClientBuilder::try_hash_prefix_length`]: crate::ClientBuilder::try_hash_prefix_length
+	#[cfg(feature = "private_searches")]
+	#[error("hash prefix length {0} is not in the range 4..=32")]
+	InvalidHashPrefixLength(u8),
+
 	// Data Verification
 	/// Data received from the API does not make sense or fails to meet sanity
 	/// requirements.
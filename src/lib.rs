@@ -21,12 +21,23 @@
 //!   saved ID for the same 'user'. This is for cases where you may want to
 //!   generate new user IDs for users of your application, giving each user
 //!   their own ID.
+//! - `user_id_store`: A utility function for loading a local user ID from a
+//!   file, generating and saving a new one if necessary. This is the
+//!   recommended way of keeping a single ID across sessions.
+//! - `mock`: Support for answering requests with canned responses instead of
+//!   contacting the API, for testing without network access.
+//! - `metrics`: Hooks for collecting metrics about requests to the API, such as
+//!   request counts, latencies, and outcomes, without depending on a specific
+//!   metrics exporter.
+//! - `refresher`: Support for keeping the segments for a video up to date in
+//!   the background, for long-running players. This requires the Tokio runtime.
 //!
 //! ## Example
 //! The following is a short example of how you might fetch the segments for a
 //! video:
 //!
 //! ```rust,no_run
+//! # async fn example() {
 //! use sponsor_block::{AcceptedActions, AcceptedCategories, Client};
 //!
 //! // This should be random, treated like a password, and stored across sessions
@@ -39,6 +50,7 @@
 //!     .ok();
 //!
 //! // Then do something with your video segments...
+//! # }
 //! ```
 
 // Linting rules
@@ -91,9 +103,14 @@ mod error;
 #[cfg(feature = "gen_user_id")]
 mod gen_user_id;
 mod segment;
+#[cfg(feature = "user_id_store")]
+mod user_id_store;
 mod util;
+mod video_id;
 
 // Public Exports
 #[cfg(feature = "gen_user_id")]
 pub use self::gen_user_id::*;
-pub use self::{client::*, error::*, segment::*};
+#[cfg(feature = "user_id_store")]
+pub use self::user_id_store::*;
+pub use self::{client::*, error::*, segment::*, video_id::*};
@@ -5,9 +5,19 @@ use std::result::Result as StdResult;
 
 use bitflags::bitflags;
 use enum_kinds::EnumKind;
-use serde::{de::Error, Deserialize, Deserializer};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::api::convert_to_action_kind;
+use crate::{
+	api::{
+		convert_action_bitflags_to_url,
+		convert_action_kind_to_bitflag,
+		convert_from_action_kind,
+		convert_to_action_kind,
+		convert_url_to_action_bitflags,
+		ACTION_KINDS,
+	},
+	error::Result as CrateResult,
+};
 
 /// The action to take on a segment.
 ///
@@ -15,9 +25,9 @@ use crate::api::convert_to_action_kind;
 /// how to handle the segment.
 ///
 /// See <https://wiki.sponsor.ajay.app/w/Types#Action_Type> for more information.
-#[derive(Clone, Copy, Debug, EnumKind, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Debug, Deserialize, EnumKind, PartialEq, PartialOrd, Serialize)]
 #[non_exhaustive]
-#[enum_kind(ActionKind, non_exhaustive, derive(Hash))]
+#[enum_kind(ActionKind, non_exhaustive, derive(Hash, Ord, PartialOrd))]
 pub enum Action {
 	/// Skip the segment. This is the default action type.
 	Skip(f32, f32),
@@ -43,6 +53,26 @@ pub enum Action {
 	FullVideo,
 }
 
+impl Action {
+	/// The start and end times of the action, if it covers a range of time.
+	pub(crate) fn time_range(&self) -> Option<(f32, f32)> {
+		match *self {
+			Action::Skip(start, end) | Action::Mute(start, end) => Some((start, end)),
+			Action::PointOfInterest(_) | Action::FullVideo => None,
+		}
+	}
+
+	/// The time the action starts at, if it has one.
+	pub(crate) fn start_time(&self) -> Option<f32> {
+		match *self {
+			Action::Skip(start, _) | Action::Mute(start, _) | Action::PointOfInterest(start) => {
+				Some(start)
+			}
+			Action::FullVideo => None,
+		}
+	}
+}
+
 bitflags! {
 	/// A struct for supplying the action types of segments you want to look for in a video.
 	#[repr(transparent)]
@@ -51,9 +81,9 @@ bitflags! {
 		const NONE = 0b0000;
 		/// Skip - take a look at [`crate::Action::Skip`] for more information.
 		const SKIP = 0b0001;
-		/// Mute - take a look at [`crate::Action::PointOfInterest`] for more information.
+		/// Mute - take a look at [`crate::Action::Mute`] for more information.
 		const MUTE = 0b0010;
-		/// Point of Interest - take a look at [`crate::Action::Mute`] for more information.
+		/// Point of Interest - take a look at [`crate::Action::PointOfInterest`] for more information.
 		const POINT_OF_INTEREST = 0b0100;
 		/// Full Video - take a look at [`crate::Action::FullVideo`] for more information.
 		const FULL_VIDEO = 0b1000;
@@ -66,7 +96,74 @@ impl Default for AcceptedActions {
 	}
 }
 
+impl AcceptedActions {
+	/// A convenience function for accepting only points in the video, such as
+	/// highlights.
+	///
+	/// This is useful for "skip to the good part" features.
+	#[must_use]
+	pub const fn points() -> Self {
+		Self::POINT_OF_INTEREST
+	}
+
+	/// Converts the accepted action types to the string the API uses for them,
+	/// such as `["skip","mute"]`.
+	///
+	/// This is useful for saving a selection, which can be restored with
+	/// [`from_api_string`].
+	///
+	/// [`from_api_string`]: Self::from_api_string
+	#[must_use]
+	pub fn to_api_string(&self) -> String {
+		convert_action_bitflags_to_url(*self)
+	}
+
+	/// Parses accepted action types from the string the API uses for them,
+	/// such as one created by [`to_api_string`].
+	///
+	/// Unrecognized action types are ignored, so that selections saved by
+	/// newer versions of the library can still be loaded.
+	///
+	/// # Errors
+	/// Returns [`Deserialization`] if the string isn't a JSON array of strings.
+	///
+	/// [`to_api_string`]: Self::to_api_string
+	/// [`Deserialization`]: crate::SponsorBlockError::Deserialization
+	pub fn from_api_string<S>(api_string: S) -> CrateResult<Self>
+	where
+		S: AsRef<str>,
+	{
+		convert_url_to_action_bitflags(api_string.as_ref())
+	}
+
+	/// Iterates over the action types that are accepted.
+	pub fn iter(&self) -> impl Iterator<Item = ActionKind> + '_ {
+		ACTION_KINDS
+			.iter()
+			.copied()
+			.filter(|kind| self.contains(kind.as_accepted()))
+	}
+}
+
+impl FromIterator<ActionKind> for AcceptedActions {
+	fn from_iter<I>(iter: I) -> Self
+	where
+		I: IntoIterator<Item = ActionKind>,
+	{
+		iter.into_iter()
+			.fold(Self::NONE, |accepted, kind| accepted | kind.as_accepted())
+	}
+}
+
 impl ActionKind {
+	/// Gets the [`AcceptedActions`] flag corresponding to the action type.
+	///
+	/// This is useful for fetching more segments like one you already have.
+	#[must_use]
+	pub fn as_accepted(&self) -> AcceptedActions {
+		convert_action_kind_to_bitflag(*self)
+	}
+
 	pub(crate) fn to_action(self, time_points: [f32; 2]) -> Action {
 		match self {
 			ActionKind::Skip => Action::Skip(time_points[0], time_points[1]),
@@ -87,8 +184,46 @@ impl<'de> Deserialize<'de> for ActionKind {
 	}
 }
 
+impl Serialize for ActionKind {
+	fn serialize<S>(&self, serializer: S) -> StdResult<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		serializer.serialize_str(convert_from_action_kind(*self))
+	}
+}
+
+#[allow(clippy::derivable_impls)]
 impl Default for ActionKind {
 	fn default() -> Self {
 		Self::Skip
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	// Uses
+	use super::AcceptedActions;
+
+	#[test]
+	fn api_string_round_trips() {
+		for accepted in [
+			AcceptedActions::all(),
+			AcceptedActions::empty(),
+			AcceptedActions::MUTE | AcceptedActions::FULL_VIDEO,
+		] {
+			assert_eq!(
+				AcceptedActions::from_api_string(accepted.to_api_string()).unwrap(),
+				accepted
+			);
+		}
+	}
+
+	#[test]
+	fn from_api_string_ignores_unknown_action_types() {
+		assert_eq!(
+			AcceptedActions::from_api_string(r#"["skip","chapter","poi"]"#).unwrap(),
+			AcceptedActions::SKIP | AcceptedActions::POINT_OF_INTEREST
+		);
+	}
+}
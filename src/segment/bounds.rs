@@ -0,0 +1,69 @@
+//! Functions for checking segments against the duration of a video.
+
+// Uses
+use crate::{Action, Segment};
+
+/// What to do with segments that extend past the end of a video.
+///
+/// See [`clamp_segments_to_duration`] for more information.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum OutOfBoundsHandling {
+	/// Remove the segment entirely.
+	Drop,
+	/// Cut off the part of the segment past the end of the video.
+	Clamp,
+}
+
+/// Removes or truncates segments that extend past the end of a video, based on
+/// `handling`.
+///
+/// This is useful when you know the actual duration of the video, since
+/// segments submitted against an older, longer version of it may no longer
+/// line up.
+///
+/// Segments that start at or after `duration`, including points past it, are
+/// always removed since there's nothing left of them to keep. Full video
+/// segments are always kept.
+pub fn clamp_segments_to_duration(
+	segments: &mut Vec<Segment>,
+	duration: f32,
+	handling: OutOfBoundsHandling,
+) {
+	segments.retain_mut(|segment| match segment.action {
+		Action::Skip(start, _) | Action::Mute(start, _) if start >= duration => false,
+		Action::Skip(_, end) | Action::Mute(_, end) if end <= duration => true,
+		Action::Skip(start, _) => match handling {
+			OutOfBoundsHandling::Drop => false,
+			OutOfBoundsHandling::Clamp => {
+				segment.action = Action::Skip(start, duration);
+				true
+			}
+		},
+		Action::Mute(start, _) => match handling {
+			OutOfBoundsHandling::Drop => false,
+			OutOfBoundsHandling::Clamp => {
+				segment.action = Action::Mute(start, duration);
+				true
+			}
+		},
+		Action::PointOfInterest(point) => point <= duration,
+		Action::FullVideo => true,
+	});
+}
+
+/// Finds the segments that extend past the end of a video.
+///
+/// These are the segments that [`clamp_segments_to_duration`] would remove or
+/// truncate.
+#[must_use]
+pub fn validate_segments_against_duration(segments: &[Segment], duration: f32) -> Vec<&Segment> {
+	segments
+		.iter()
+		.filter(|segment| match segment.action {
+			Action::Skip(_, end) | Action::Mute(_, end) => end > duration,
+			Action::PointOfInterest(point) => point > duration,
+			Action::FullVideo => false,
+		})
+		.collect()
+}
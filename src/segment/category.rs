@@ -4,9 +4,19 @@
 use std::result::Result as StdResult;
 
 use bitflags::bitflags;
-use serde::{de::Error, Deserialize, Deserializer};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::api::convert_to_category;
+use crate::{
+	api::{
+		convert_category_bitflags_to_url,
+		convert_category_to_bitflag,
+		convert_from_category,
+		convert_to_category,
+		convert_url_to_category_bitflags,
+		CATEGORIES,
+	},
+	error::Result as CrateResult,
+};
 
 /// A video segment category, containing timestamp information.
 ///
@@ -74,6 +84,104 @@ pub enum Category {
 	ExclusiveAccess,
 }
 
+impl Category {
+	/// Whether segments of the category mark a region of the video that can
+	/// be skipped.
+	///
+	/// This is `false` for categories that are only informational or that mark
+	/// a single point, such as [`Highlight`] and [`ExclusiveAccess`].
+	///
+	/// [`Highlight`]: Self::Highlight
+	/// [`ExclusiveAccess`]: Self::ExclusiveAccess
+	#[must_use]
+	pub fn is_skippable(&self) -> bool {
+		match self {
+			Self::Sponsor
+			| Self::UnpaidSelfPromotion
+			| Self::InteractionReminder
+			| Self::IntermissionIntroAnimation
+			| Self::EndcardsCredits
+			| Self::PreviewRecap
+			| Self::NonMusic
+			| Self::FillerTangent => true,
+			Self::Highlight | Self::ExclusiveAccess => false,
+		}
+	}
+
+	/// Whether segments of the category mark a single point in the video
+	/// rather than a region.
+	///
+	/// Points aren't meant to be skipped, but may be used as a point to *skip
+	/// to*.
+	#[must_use]
+	pub fn is_point(&self) -> bool {
+		match self {
+			Self::Highlight => true,
+			Self::Sponsor
+			| Self::UnpaidSelfPromotion
+			| Self::InteractionReminder
+			| Self::IntermissionIntroAnimation
+			| Self::EndcardsCredits
+			| Self::PreviewRecap
+			| Self::NonMusic
+			| Self::FillerTangent
+			| Self::ExclusiveAccess => false,
+		}
+	}
+
+	/// Gets the human-readable name of the category, as used by the
+	/// SponsorBlock wiki.
+	///
+	/// This is useful for labelling segments in a UI.
+	#[must_use]
+	pub fn display_name(&self) -> &'static str {
+		match self {
+			Self::Sponsor => "Sponsor",
+			Self::UnpaidSelfPromotion => "Unpaid/Self-Promotion",
+			Self::InteractionReminder => "Interaction Reminder",
+			Self::Highlight => "Highlight",
+			Self::IntermissionIntroAnimation => "Intermission/Intro Animation",
+			Self::EndcardsCredits => "Endcards/Credits",
+			Self::PreviewRecap => "Preview/Recap",
+			Self::NonMusic => "Non-Music",
+			Self::FillerTangent => "Filler Tangent",
+			Self::ExclusiveAccess => "Exclusive Access",
+		}
+	}
+
+	/// Gets the URL of the SponsorBlock wiki page that explains the category.
+	///
+	/// This is useful for linking to more information about a segment's
+	/// category in a UI.
+	#[must_use]
+	pub fn wiki_url(&self) -> &'static str {
+		match self {
+			Self::Sponsor => "https://wiki.sponsor.ajay.app/w/Sponsor",
+			Self::UnpaidSelfPromotion => "https://wiki.sponsor.ajay.app/w/Unpaid/Self_Promotion",
+			Self::InteractionReminder => {
+				"https://wiki.sponsor.ajay.app/w/Interaction_Reminder_(Subscribe)"
+			}
+			Self::Highlight => "https://wiki.sponsor.ajay.app/w/Highlight",
+			Self::IntermissionIntroAnimation => {
+				"https://wiki.sponsor.ajay.app/w/Intermission/Intro_Animation"
+			}
+			Self::EndcardsCredits => "https://wiki.sponsor.ajay.app/w/Endcards/Credits",
+			Self::PreviewRecap => "https://wiki.sponsor.ajay.app/w/Preview/Recap",
+			Self::NonMusic => "https://wiki.sponsor.ajay.app/w/Music:_Non-Music_Section",
+			Self::FillerTangent => "https://wiki.sponsor.ajay.app/w/Filler_Tangent",
+			Self::ExclusiveAccess => "https://wiki.sponsor.ajay.app/w/Exclusive_Access",
+		}
+	}
+
+	/// Gets the [`AcceptedCategories`] flag corresponding to the category.
+	///
+	/// This is useful for fetching more segments like one you already have.
+	#[must_use]
+	pub fn as_accepted(&self) -> AcceptedCategories {
+		convert_category_to_bitflag(*self)
+	}
+}
+
 impl<'de> Deserialize<'de> for Category {
 	fn deserialize<D>(deserializer: D) -> StdResult<Self, D::Error>
 	where
@@ -84,6 +192,16 @@ impl<'de> Deserialize<'de> for Category {
 	}
 }
 
+impl Serialize for Category {
+	fn serialize<S>(&self, serializer: S) -> StdResult<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		serializer.serialize_str(convert_from_category(*self))
+	}
+}
+
+#[allow(clippy::derivable_impls)]
 impl Default for Category {
 	fn default() -> Self {
 		Self::Sponsor
@@ -124,3 +242,84 @@ impl Default for AcceptedCategories {
 		Self::all()
 	}
 }
+
+impl AcceptedCategories {
+	/// Converts the accepted categories to the string the API uses for them,
+	/// such as `["sponsor","intro"]`.
+	///
+	/// This is useful for saving a selection, which can be restored with
+	/// [`from_api_string`].
+	///
+	/// [`from_api_string`]: Self::from_api_string
+	#[must_use]
+	pub fn to_api_string(&self) -> String {
+		convert_category_bitflags_to_url(*self)
+	}
+
+	/// Parses accepted categories from the string the API uses for them, such
+	/// as one created by [`to_api_string`].
+	///
+	/// Unrecognized categories are ignored, so that selections saved by newer
+	/// versions of the library can still be loaded.
+	///
+	/// # Errors
+	/// Returns [`Deserialization`] if the string isn't a JSON array of strings.
+	///
+	/// [`to_api_string`]: Self::to_api_string
+	/// [`Deserialization`]: crate::SponsorBlockError::Deserialization
+	pub fn from_api_string<S>(api_string: S) -> CrateResult<Self>
+	where
+		S: AsRef<str>,
+	{
+		convert_url_to_category_bitflags(api_string.as_ref())
+	}
+
+	/// Iterates over the categories that are accepted.
+	pub fn iter(&self) -> impl Iterator<Item = Category> + '_ {
+		CATEGORIES
+			.iter()
+			.copied()
+			.filter(|category| self.contains(category.as_accepted()))
+	}
+}
+
+impl FromIterator<Category> for AcceptedCategories {
+	fn from_iter<I>(iter: I) -> Self
+	where
+		I: IntoIterator<Item = Category>,
+	{
+		iter.into_iter().fold(Self::NONE, |accepted, category| {
+			accepted | category.as_accepted()
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	// Uses
+	use super::AcceptedCategories;
+
+	#[test]
+	fn api_string_round_trips() {
+		for accepted in [
+			AcceptedCategories::all(),
+			AcceptedCategories::empty(),
+			AcceptedCategories::SPONSOR
+				| AcceptedCategories::HIGHLIGHT
+				| AcceptedCategories::EXCLUSIVE_ACCESS,
+		] {
+			assert_eq!(
+				AcceptedCategories::from_api_string(accepted.to_api_string()).unwrap(),
+				accepted
+			);
+		}
+	}
+
+	#[test]
+	fn from_api_string_ignores_unknown_categories() {
+		assert_eq!(
+			AcceptedCategories::from_api_string(r#"["sponsor","not_a_category","intro"]"#).unwrap(),
+			AcceptedCategories::SPONSOR | AcceptedCategories::INTERMISSION_INTRO_ANIMATION
+		);
+	}
+}
@@ -0,0 +1,76 @@
+//! Functions for comparing lists of segments.
+
+// Uses
+use std::collections::{HashMap, HashSet};
+
+use crate::Segment;
+
+/// The differences between two lists of segments, as found by
+/// [`diff_segments`].
+#[derive(Clone, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct SegmentDiff {
+	/// The segments that are only in the new list.
+	pub added: Vec<Segment>,
+	/// The segments that are only in the old list.
+	pub removed: Vec<Segment>,
+	/// The segments that are in both lists, but with a different number of
+	/// votes.
+	pub vote_changed: Vec<VoteChange>,
+}
+
+impl SegmentDiff {
+	/// Whether there are no differences at all.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.added.is_empty() && self.removed.is_empty() && self.vote_changed.is_empty()
+	}
+}
+
+/// A change in the number of votes a segment has.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct VoteChange {
+	/// The segment, as it is in the new list.
+	pub segment: Segment,
+	/// The number of votes the segment had in the old list.
+	pub old_votes: i32,
+	/// The number of votes the segment has in the new list.
+	pub new_votes: i32,
+}
+
+/// Compares two lists of segments, such as from fetching the same video at
+/// different times.
+///
+/// Segments are matched by their [`uuid`](Segment::uuid). Each part of the
+/// result is in the order the segments appear in the list they come from.
+#[must_use]
+pub fn diff_segments(old: &[Segment], new: &[Segment]) -> SegmentDiff {
+	let old_by_uuid = old
+		.iter()
+		.map(|s| (s.uuid.as_str(), s))
+		.collect::<HashMap<_, _>>();
+	let new_uuids = new.iter().map(|s| s.uuid.as_str()).collect::<HashSet<_>>();
+
+	let mut diff = SegmentDiff::default();
+	for segment in new {
+		match old_by_uuid.get(segment.uuid.as_str()) {
+			None => diff.added.push(segment.clone()),
+			Some(old_segment) if old_segment.votes != segment.votes => {
+				diff.vote_changed.push(VoteChange {
+					segment: segment.clone(),
+					old_votes: old_segment.votes,
+					new_votes: segment.votes,
+				});
+			}
+			Some(_) => {}
+		}
+	}
+	diff.removed = old
+		.iter()
+		.filter(|s| !new_uuids.contains(s.uuid.as_str()))
+		.cloned()
+		.collect();
+
+	diff
+}
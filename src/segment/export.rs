@@ -0,0 +1,151 @@
+//! Functions for exporting segments to formats that other software
+//! understands.
+
+// Uses
+use std::fmt::Write;
+
+use crate::{Action, Segment};
+
+/// How long the cues for points in the video last, in milliseconds.
+///
+/// WebVTT cues must end after they start, so points can't be represented
+/// exactly.
+const POINT_CUE_DURATION: u64 = 1000;
+
+/// Exports segments as a [WebVTT](https://www.w3.org/TR/webvtt1/) file, such
+/// as for players that can load chapters from one.
+///
+/// Each segment becomes a cue labelled with the
+/// [category's name](crate::Category::display_name), identified by the
+/// segment's UUID. Cues are sorted by start time, and may overlap if the
+/// segments do. Points in the video, such as highlights, and segments that
+/// don't end after they start (to the millisecond) become cues lasting one
+/// second from their start. Full-video labels don't have any times, so they're
+/// left out.
+#[must_use]
+pub fn segments_to_webvtt(segments: &[Segment]) -> String {
+	let mut cues = segments
+		.iter()
+		.filter_map(|segment| {
+			let (start, end) = match segment.action {
+				Action::Skip(start, end) | Action::Mute(start, end) => {
+					(to_millis(start), to_millis(end))
+				}
+				Action::PointOfInterest(point) => (to_millis(point), 0),
+				Action::FullVideo => return None,
+			};
+			let end = if end > start {
+				end
+			} else {
+				start + POINT_CUE_DURATION
+			};
+			Some((start, end, segment))
+		})
+		.collect::<Vec<_>>();
+	cues.sort_by_key(|&(start, ..)| start);
+
+	let mut webvtt = String::from("WEBVTT\n");
+	for (start, end, segment) in cues {
+		// Writing to a `String` can't fail
+		let _ = write!(
+			webvtt,
+			"\n{}\n{} --> {}\n{}\n",
+			segment.uuid,
+			to_webvtt_timestamp(start),
+			to_webvtt_timestamp(end),
+			segment.category.display_name()
+		);
+	}
+
+	webvtt
+}
+
+/// Converts a time in seconds to whole milliseconds, which is the precision of
+/// WebVTT timestamps.
+fn to_millis(seconds: f32) -> u64 {
+	(f64::from(seconds) * 1000.0).round().max(0.0) as u64
+}
+
+/// Formats a time in milliseconds as a WebVTT timestamp, like `01:02:03.456`.
+fn to_webvtt_timestamp(total_millis: u64) -> String {
+	let (total_seconds, millis) = (total_millis / 1000, total_millis % 1000);
+	let (total_minutes, seconds) = (total_seconds / 60, total_seconds % 60);
+	let (hours, minutes) = (total_minutes / 60, total_minutes % 60);
+
+	format!("{hours:02}:{minutes:02}:{seconds:02}.{millis:03}")
+}
+
+#[cfg(test)]
+mod tests {
+	// Uses
+	use super::segments_to_webvtt;
+	use crate::{Action, Category, Segment};
+
+	fn segment(uuid: &str, category: Category, action: Action) -> Segment {
+		Segment {
+			category,
+			action,
+			uuid: uuid.to_owned(),
+			locked: false,
+			votes: 0,
+			video_duration_on_submission: None,
+			additional_info: None,
+		}
+	}
+
+	#[test]
+	fn segments_to_webvtt_golden() {
+		let segments = [
+			segment(
+				"credits",
+				Category::EndcardsCredits,
+				Action::Skip(3723.5, 3785.25),
+			),
+			segment(
+				"highlight",
+				Category::Highlight,
+				Action::PointOfInterest(95.0),
+			),
+			segment("sponsor", Category::Sponsor, Action::Skip(12.0, 34.567)),
+			segment("music", Category::NonMusic, Action::Mute(3599.9996, 3600.0)),
+			segment("full", Category::ExclusiveAccess, Action::FullVideo),
+			segment("empty", Category::FillerTangent, Action::Skip(50.0, 50.0)),
+			segment("short", Category::PreviewRecap, Action::Skip(60.0, 60.25)),
+		];
+
+		assert_eq!(
+			segments_to_webvtt(&segments),
+			"WEBVTT
+
+sponsor
+00:00:12.000 --> 00:00:34.567
+Sponsor
+
+empty
+00:00:50.000 --> 00:00:51.000
+Filler Tangent
+
+short
+00:01:00.000 --> 00:01:00.250
+Preview/Recap
+
+highlight
+00:01:35.000 --> 00:01:36.000
+Highlight
+
+music
+01:00:00.000 --> 01:00:01.000
+Non-Music
+
+credits
+01:02:03.500 --> 01:03:05.250
+Endcards/Credits
+"
+		);
+	}
+
+	#[test]
+	fn segments_to_webvtt_without_segments() {
+		assert_eq!(segments_to_webvtt(&[]), "WEBVTT\n");
+	}
+}
@@ -0,0 +1,99 @@
+//! Functions for filtering lists of segments.
+
+// Uses
+use std::collections::HashSet;
+
+use crate::{Action, Segment};
+
+/// Removes all segments whose submitter is shadow-banned.
+///
+/// This relies on [`AdditionalSegmentInfo::shadow_banned`], so segments without
+/// additional info are left untouched. Most shadow-banned segments are already
+/// hidden by the API.
+///
+/// [`AdditionalSegmentInfo::shadow_banned`]: crate::AdditionalSegmentInfo::shadow_banned
+#[must_use]
+pub fn exclude_shadow_banned(segments: Vec<Segment>) -> Vec<Segment> {
+	segments
+		.into_iter()
+		.filter(|s| !s.additional_info.as_ref().is_some_and(|i| i.shadow_banned))
+		.collect()
+}
+
+/// Keeps only the segments whose submitter is shadow-banned.
+///
+/// This is the opposite of [`exclude_shadow_banned`], and is mostly useful for
+/// moderation. Segments without additional info are dropped, since it's
+/// unknown whether their submitter is shadow-banned.
+#[must_use]
+pub fn shadow_banned_only(segments: Vec<Segment>) -> Vec<Segment> {
+	segments
+		.into_iter()
+		.filter(|s| s.additional_info.as_ref().is_some_and(|i| i.shadow_banned))
+		.collect()
+}
+
+/// Removes duplicate segments, keeping only the first occurrence of each.
+///
+/// Segments are considered duplicates if they have the same
+/// [`uuid`](Segment::uuid), which is what identifies a segment. This is useful
+/// for combining the results of several fetches.
+#[must_use]
+pub fn dedupe_by_uuid(segments: Vec<Segment>) -> Vec<Segment> {
+	let mut seen = HashSet::with_capacity(segments.len());
+	segments
+		.into_iter()
+		.filter(|s| seen.insert(s.uuid.clone()))
+		.collect()
+}
+
+/// Gets the segments that overlap a window of time in a video, in seconds.
+///
+/// This is useful for players and UIs that only deal with part of a video at a
+/// time, such as around the current playback position. Ranged segments are
+/// included if any part of them falls within the window, and points of
+/// interest are included if they're inside it. Full-video labels apply to the
+/// entire video, so they're always included.
+///
+/// The segments are returned in their original order.
+#[must_use]
+pub fn segments_in_window(segments: &[Segment], start: f32, end: f32) -> Vec<&Segment> {
+	segments
+		.iter()
+		.filter(|s| match s.action {
+			Action::Skip(segment_start, segment_end) | Action::Mute(segment_start, segment_end) => {
+				segment_start <= end && segment_end >= start
+			}
+			Action::PointOfInterest(point) => point >= start && point <= end,
+			Action::FullVideo => true,
+		})
+		.collect()
+}
+
+/// Gets the segments that are meant to inform the user rather than be skipped,
+/// such as for showing a "heads up" banner separately from the skip list.
+///
+/// A segment is informational if:
+/// - It's a full-video label ([`Action::FullVideo`]), or
+/// - Its category isn't [skippable](crate::Category::is_skippable) and doesn't
+///   mark a point, such as [`ExclusiveAccess`], or
+/// - It's a point of interest, such as a [`Highlight`], and
+///   `include_highlights` is `true`.
+///
+/// The segments are returned in their original order.
+///
+/// [`ExclusiveAccess`]: crate::Category::ExclusiveAccess
+/// [`Highlight`]: crate::Category::Highlight
+#[must_use]
+pub fn informational_segments(segments: &[Segment], include_highlights: bool) -> Vec<&Segment> {
+	segments
+		.iter()
+		.filter(|s| match s.action {
+			Action::FullVideo => true,
+			Action::PointOfInterest(_) => include_highlights,
+			Action::Skip(..) | Action::Mute(..) => {
+				!s.category.is_skippable() && !s.category.is_point()
+			}
+		})
+		.collect()
+}
@@ -0,0 +1,59 @@
+//! Functions for grouping and counting lists of segments.
+
+// Uses
+use std::collections::HashMap;
+
+use crate::{ActionKind, Category, Segment};
+
+/// Groups segments by their category.
+///
+/// Segments keep their original relative order within each group.
+#[must_use]
+pub fn group_by_category(segments: Vec<Segment>) -> HashMap<Category, Vec<Segment>> {
+	let mut groups: HashMap<Category, Vec<Segment>> = HashMap::new();
+	for segment in segments {
+		groups.entry(segment.category).or_default().push(segment);
+	}
+	groups
+}
+
+/// Groups segments by their kind of action.
+///
+/// Segments keep their original relative order within each group.
+#[must_use]
+pub fn group_by_action(segments: Vec<Segment>) -> HashMap<ActionKind, Vec<Segment>> {
+	let mut groups: HashMap<ActionKind, Vec<Segment>> = HashMap::new();
+	for segment in segments {
+		groups
+			.entry(segment.action_kind())
+			.or_default()
+			.push(segment);
+	}
+	groups
+}
+
+/// Counts segments by their category.
+///
+/// This is the same as the lengths of the groups from [`group_by_category`],
+/// without taking ownership of the segments.
+#[must_use]
+pub fn count_by_category(segments: &[Segment]) -> HashMap<Category, usize> {
+	let mut counts: HashMap<Category, usize> = HashMap::new();
+	for segment in segments {
+		*counts.entry(segment.category).or_default() += 1;
+	}
+	counts
+}
+
+/// Counts segments by their kind of action.
+///
+/// This is the same as the lengths of the groups from [`group_by_action`],
+/// without taking ownership of the segments.
+#[must_use]
+pub fn count_by_action(segments: &[Segment]) -> HashMap<ActionKind, usize> {
+	let mut counts: HashMap<ActionKind, usize> = HashMap::new();
+	for segment in segments {
+		*counts.entry(segment.action_kind()).or_default() += 1;
+	}
+	counts
+}
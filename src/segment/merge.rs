@@ -0,0 +1,469 @@
+//! Functions for merging overlapping segments.
+
+// Uses
+use std::{cmp::Ordering, collections::HashMap};
+
+use crate::{Action, ActionKind, Category, Segment};
+
+/// How to decide which segment wins when merging overlapping segments.
+///
+/// See [`merge_overlapping`] for more information.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum MergeStrategy {
+	/// Keep the segment with the most votes.
+	///
+	/// Ties are broken by preferring locked segments, then by whichever was
+	/// provided first.
+	#[default]
+	HighestVotes,
+	/// Keep a locked segment over any unlocked ones, even if the unlocked ones
+	/// have more votes.
+	///
+	/// Among segments that are all locked or all unlocked, the one with the
+	/// most votes is kept, and ties are broken by whichever was provided
+	/// first. Only VIPs lock segments, so a locked segment has always been
+	/// approved by one, and players should generally honour it.
+	PreferLocked,
+	/// Keep the segment with the most votes, like [`HighestVotes`], but widen
+	/// its bounds to cover every segment it was merged with.
+	///
+	/// [`HighestVotes`]: Self::HighestVotes
+	WidestRange,
+}
+
+/// Merges overlapping segments of the same category and action type, keeping
+/// one segment per group of overlapping ones according to `strategy`.
+///
+/// Only segments with a time range ([`Action::Skip`] and [`Action::Mute`]) are
+/// merged. Segments that touch but don't overlap are left separate. All other
+/// segments are kept as-is.
+///
+/// The result is sorted by start time.
+#[must_use]
+pub fn merge_overlapping(segments: Vec<Segment>, strategy: MergeStrategy) -> Vec<Segment> {
+	// Split the segments into groups that can be merged with each other
+	let mut result = Vec::with_capacity(segments.len());
+	let mut groups = HashMap::<(Category, ActionKind), Vec<_>>::new();
+	for (index, segment) in segments.into_iter().enumerate() {
+		if let Some(range) = segment.action.time_range() {
+			groups
+				.entry((segment.category, segment.action_kind()))
+				.or_default()
+				.push((index, range, segment));
+		} else {
+			result.push(segment);
+		}
+	}
+
+	// Merge each group
+	for mut group in groups.into_values() {
+		group.sort_by(|(_, (a, _), _), (_, (b, _), _)| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+		let mut cluster: Vec<(usize, Segment)> = Vec::new();
+		let mut cluster_end = f32::NEG_INFINITY;
+		for (index, (start, end), segment) in group {
+			if !cluster.is_empty() && start >= cluster_end {
+				result.push(resolve_cluster(&mut cluster, strategy));
+			}
+			cluster_end = if cluster.is_empty() {
+				end
+			} else {
+				cluster_end.max(end)
+			};
+			cluster.push((index, segment));
+		}
+		if !cluster.is_empty() {
+			result.push(resolve_cluster(&mut cluster, strategy));
+		}
+	}
+
+	result.sort_by(Segment::cmp_by_start_time);
+	result
+}
+
+/// Picks the winning segment out of a non-empty cluster of overlapping ones,
+/// emptying the cluster.
+fn resolve_cluster(cluster: &mut Vec<(usize, Segment)>, strategy: MergeStrategy) -> Segment {
+	let bounds = cluster
+		.iter()
+		.filter_map(|(_, segment)| segment.action.time_range())
+		.fold((f32::INFINITY, f32::NEG_INFINITY), |acc, (start, end)| {
+			(acc.0.min(start), acc.1.max(end))
+		});
+
+	let winner_index = (0..cluster.len())
+		.max_by(|&a, &b| {
+			let (index_a, segment_a) = &cluster[a];
+			let (index_b, segment_b) = &cluster[b];
+			let by_votes = segment_a.votes.cmp(&segment_b.votes);
+			let by_locked = segment_a.locked.cmp(&segment_b.locked);
+			match strategy {
+				MergeStrategy::HighestVotes | MergeStrategy::WidestRange => {
+					by_votes.then(by_locked)
+				}
+				MergeStrategy::PreferLocked => by_locked.then(by_votes),
+			}
+			// Earlier segments win ties
+			.then_with(|| index_b.cmp(index_a))
+		})
+		.expect("clusters are never empty");
+	let (_, mut winner) = cluster.swap_remove(winner_index);
+	cluster.clear();
+
+	if strategy == MergeStrategy::WidestRange {
+		winner.action = match winner.action {
+			Action::Skip(..) => Action::Skip(bounds.0, bounds.1),
+			Action::Mute(..) => Action::Mute(bounds.0, bounds.1),
+			other @ (Action::PointOfInterest(_) | Action::FullVideo) => other,
+		};
+	}
+
+	winner
+}
+
+/// Resolves overlaps between segments of different categories, keeping the
+/// segment whose category comes first in `priority`.
+///
+/// This is for players, which can only act on one segment at a time, and
+/// would otherwise skip the same region twice. Unlike [`merge_overlapping`],
+/// overlapping segments of the *same* category are left alone, so the two can
+/// be combined to fully clean up a list of segments.
+///
+/// Only segments with a time range ([`Action::Skip`] and [`Action::Mute`]) can
+/// overlap. Segments that touch but don't overlap are both kept. Categories
+/// missing from `priority` rank below all those in it, and ties between
+/// equally-ranked categories are won by whichever segment was provided first.
+/// A segment that loses to a higher-priority one doesn't block any other
+/// segments, so a low-priority segment is still kept if the only thing it
+/// overlapped was removed. All other segments are kept as-is.
+///
+/// The result is sorted by start time.
+#[must_use]
+pub fn resolve_overlaps_by_priority(segments: Vec<Segment>, priority: &[Category]) -> Vec<Segment> {
+	let rank = |category: Category| {
+		priority
+			.iter()
+			.position(|&prioritized| prioritized == category)
+			.unwrap_or(priority.len())
+	};
+
+	// Split off the segments that can't overlap
+	let mut result = Vec::with_capacity(segments.len());
+	let mut ranged = Vec::new();
+	for segment in segments {
+		if let Some(range) = segment.action.time_range() {
+			ranged.push((rank(segment.category), range, segment));
+		} else {
+			result.push(segment);
+		}
+	}
+
+	// Keep each segment unless it overlaps a higher-priority one that was kept
+	// (the sort is stable, so earlier segments win ties)
+	ranged.sort_by_key(|(rank, ..)| *rank);
+	let mut kept: Vec<(Category, (f32, f32))> = Vec::with_capacity(ranged.len());
+	for (_, (start, end), segment) in ranged {
+		let overlaps = kept.iter().any(|&(category, (kept_start, kept_end))| {
+			category != segment.category && start < kept_end && kept_start < end
+		});
+		if !overlaps {
+			kept.push((segment.category, (start, end)));
+			result.push(segment);
+		}
+	}
+
+	result.sort_by(Segment::cmp_by_start_time);
+	result
+}
+
+#[cfg(test)]
+mod tests {
+	// Uses
+	use super::{merge_overlapping, resolve_overlaps_by_priority, MergeStrategy};
+	use crate::{Action, Category, Segment};
+
+	fn segment(
+		uuid: &str,
+		category: Category,
+		action: Action,
+		votes: i32,
+		locked: bool,
+	) -> Segment {
+		Segment {
+			category,
+			action,
+			uuid: uuid.to_owned(),
+			locked,
+			votes,
+			video_duration_on_submission: None,
+			additional_info: None,
+		}
+	}
+
+	fn uuids(segments: &[Segment]) -> Vec<&str> {
+		segments
+			.iter()
+			.map(|segment| segment.uuid.as_str())
+			.collect()
+	}
+
+	#[test]
+	fn merge_overlapping_winners() {
+		let sponsor = |uuid, start, end, votes, locked| {
+			segment(
+				uuid,
+				Category::Sponsor,
+				Action::Skip(start, end),
+				votes,
+				locked,
+			)
+		};
+
+		// (segments, strategy, expected winners)
+		let cases = [
+			// The most votes wins
+			(
+				vec![
+					sponsor("a", 0.0, 10.0, 5, false),
+					sponsor("b", 5.0, 15.0, 10, false),
+				],
+				MergeStrategy::HighestVotes,
+				vec!["b"],
+			),
+			// Locked segments break ties on votes
+			(
+				vec![
+					sponsor("a", 0.0, 10.0, 5, false),
+					sponsor("b", 5.0, 15.0, 5, true),
+				],
+				MergeStrategy::HighestVotes,
+				vec!["b"],
+			),
+			// But don't beat more votes
+			(
+				vec![
+					sponsor("a", 0.0, 10.0, 5, false),
+					sponsor("b", 5.0, 15.0, 1, true),
+				],
+				MergeStrategy::HighestVotes,
+				vec!["a"],
+			),
+			// Full ties go to the segment provided first
+			(
+				vec![
+					sponsor("a", 5.0, 15.0, 5, false),
+					sponsor("b", 0.0, 10.0, 5, false),
+				],
+				MergeStrategy::HighestVotes,
+				vec!["a"],
+			),
+			// A locked segment with fewer votes wins when preferring locked segments
+			(
+				vec![
+					sponsor("a", 0.0, 10.0, 1, true),
+					sponsor("b", 5.0, 15.0, 10, false),
+				],
+				MergeStrategy::PreferLocked,
+				vec!["a"],
+			),
+			// Between unlocked segments, the most votes still wins
+			(
+				vec![
+					sponsor("a", 0.0, 10.0, 1, false),
+					sponsor("b", 5.0, 15.0, 10, false),
+				],
+				MergeStrategy::PreferLocked,
+				vec!["b"],
+			),
+			// Between locked segments too, with ties going to the first one
+			(
+				vec![
+					sponsor("a", 0.0, 10.0, 3, true),
+					sponsor("b", 5.0, 15.0, 3, true),
+					sponsor("c", 8.0, 12.0, 20, false),
+				],
+				MergeStrategy::PreferLocked,
+				vec!["a"],
+			),
+			// Chains of overlapping segments are merged together
+			(
+				vec![
+					sponsor("a", 0.0, 10.0, 1, false),
+					sponsor("b", 9.0, 20.0, 2, false),
+					sponsor("c", 19.0, 30.0, 3, false),
+				],
+				MergeStrategy::HighestVotes,
+				vec!["c"],
+			),
+			// Segments that only touch aren't merged
+			(
+				vec![
+					sponsor("a", 0.0, 10.0, 1, false),
+					sponsor("b", 10.0, 20.0, 2, false),
+				],
+				MergeStrategy::HighestVotes,
+				vec!["a", "b"],
+			),
+		];
+
+		for (segments, strategy, expected) in cases {
+			let description = format!("{strategy:?} with {:?}", uuids(&segments));
+			assert_eq!(
+				uuids(&merge_overlapping(segments, strategy)),
+				expected,
+				"{description}"
+			);
+		}
+	}
+
+	#[test]
+	fn merge_overlapping_only_merges_the_same_category_and_action() {
+		let segments = vec![
+			segment("a", Category::Sponsor, Action::Skip(0.0, 10.0), 1, false),
+			segment(
+				"b",
+				Category::UnpaidSelfPromotion,
+				Action::Skip(1.0, 10.0),
+				1,
+				false,
+			),
+			segment("c", Category::Sponsor, Action::Mute(2.0, 10.0), 1, false),
+			segment(
+				"d",
+				Category::Highlight,
+				Action::PointOfInterest(3.0),
+				1,
+				false,
+			),
+			segment(
+				"e",
+				Category::Highlight,
+				Action::PointOfInterest(3.0),
+				1,
+				false,
+			),
+			segment("f", Category::ExclusiveAccess, Action::FullVideo, 1, false),
+		];
+
+		for strategy in [
+			MergeStrategy::HighestVotes,
+			MergeStrategy::PreferLocked,
+			MergeStrategy::WidestRange,
+		] {
+			assert_eq!(
+				uuids(&merge_overlapping(segments.clone(), strategy)),
+				["f", "a", "b", "c", "d", "e"],
+				"{strategy:?}"
+			);
+		}
+	}
+
+	#[test]
+	fn merge_overlapping_widest_range() {
+		let merged = merge_overlapping(
+			vec![
+				segment("a", Category::Sponsor, Action::Mute(0.0, 10.0), 1, true),
+				segment("b", Category::Sponsor, Action::Mute(5.0, 15.0), 10, false),
+				segment("c", Category::Sponsor, Action::Mute(12.0, 14.0), 1, false),
+			],
+			MergeStrategy::WidestRange,
+		);
+
+		assert_eq!(uuids(&merged), ["b"]);
+		assert_eq!(merged[0].action, Action::Mute(0.0, 15.0));
+	}
+
+	#[test]
+	fn resolve_overlaps_by_priority_keeps() {
+		let skip = |uuid, category, start, end| {
+			segment(uuid, category, Action::Skip(start, end), 0, false)
+		};
+		let priority = [Category::Sponsor, Category::UnpaidSelfPromotion];
+
+		// (segments, expected survivors)
+		let cases = [
+			// The higher-priority category wins, regardless of order
+			(
+				vec![
+					skip("a", Category::UnpaidSelfPromotion, 0.0, 10.0),
+					skip("b", Category::Sponsor, 5.0, 15.0),
+				],
+				vec!["b"],
+			),
+			// Categories missing from the list rank below every listed one
+			(
+				vec![
+					skip("a", Category::EndcardsCredits, 0.0, 10.0),
+					skip("b", Category::UnpaidSelfPromotion, 5.0, 15.0),
+				],
+				vec!["b"],
+			),
+			// And ties between them go to the segment provided first
+			(
+				vec![
+					skip("a", Category::EndcardsCredits, 5.0, 15.0),
+					skip("b", Category::PreviewRecap, 0.0, 10.0),
+				],
+				vec!["a"],
+			),
+			// Overlaps within a single category are left alone
+			(
+				vec![
+					skip("a", Category::Sponsor, 0.0, 10.0),
+					skip("b", Category::Sponsor, 5.0, 15.0),
+				],
+				vec!["a", "b"],
+			),
+			// Segments that only touch don't conflict
+			(
+				vec![
+					skip("a", Category::UnpaidSelfPromotion, 0.0, 10.0),
+					skip("b", Category::Sponsor, 10.0, 20.0),
+				],
+				vec!["a", "b"],
+			),
+			// A segment that lost doesn't block the segments it overlaps
+			(
+				vec![
+					skip("a", Category::Sponsor, 0.0, 10.0),
+					skip("b", Category::UnpaidSelfPromotion, 5.0, 20.0),
+					skip("c", Category::EndcardsCredits, 15.0, 25.0),
+				],
+				vec!["a", "c"],
+			),
+		];
+
+		for (segments, expected) in cases {
+			let description = format!("{:?}", uuids(&segments));
+			assert_eq!(
+				uuids(&resolve_overlaps_by_priority(segments, &priority)),
+				expected,
+				"{description}"
+			);
+		}
+	}
+
+	#[test]
+	fn resolve_overlaps_by_priority_keeps_non_ranged_segments() {
+		let segments = vec![
+			segment("a", Category::Sponsor, Action::Skip(0.0, 10.0), 0, false),
+			segment(
+				"b",
+				Category::Highlight,
+				Action::PointOfInterest(5.0),
+				0,
+				false,
+			),
+			segment("c", Category::ExclusiveAccess, Action::FullVideo, 0, false),
+		];
+
+		assert_eq!(
+			uuids(&resolve_overlaps_by_priority(
+				segments,
+				&[Category::Sponsor]
+			)),
+			["c", "a", "b"]
+		);
+	}
+}
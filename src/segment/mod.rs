@@ -1,25 +1,52 @@
 //! Everything to do with segments.
 
 // Uses
-use serde::Deserialize;
+use std::{cmp::Ordering, time::SystemTime};
+
+use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 
 use crate::{
-	util::de::{bool_from_integer_str, datetime_from_millis_timestamp},
+	util::{
+		de::{bool_from_integer_str, datetime_from_millis_timestamp},
+		ser::{bool_to_integer, datetime_to_millis_timestamp},
+	},
 	Client,
 	Result,
 };
 
 // Modules
 mod action;
+mod bounds;
 mod category;
+mod diff;
+mod export;
+mod filter;
+mod group;
+mod merge;
+mod skip;
+mod uuid;
 
 // Public Exports
-pub use self::{action::*, category::*};
+pub use self::{
+	action::*,
+	bounds::*,
+	category::*,
+	diff::*,
+	export::*,
+	filter::*,
+	group::*,
+	merge::*,
+	skip::*,
+	uuid::*,
+};
 
 /// A segment, representing a section or point in time in a video that is worth
 /// skipping or otherwise treating specially.
-#[derive(Clone, Debug, PartialEq, PartialOrd)]
+///
+/// Segments can be serialized and deserialized, such as for storing them for
+/// offline use. The format is the library's own rather than the API's.
+#[derive(Clone, Debug, Deserialize, PartialEq, PartialOrd, Serialize)]
 #[non_exhaustive]
 pub struct Segment {
 	/// The kind of segment.
@@ -27,9 +54,17 @@ pub struct Segment {
 	/// What action the submitter recommended to take for the segment.
 	/// This also encodes the time information if it is relevant.
 	pub action: Action,
-	/// The UUID of the segment submitter.
+	/// The UUID of the segment.
+	///
+	/// This uniquely identifies the segment, so two segments with the same
+	/// UUID are the same segment, even if other details such as the vote count
+	/// differ between fetches. See [`dedupe_by_uuid`] for removing duplicates.
 	pub uuid: String,
 	/// Whether the segment is locked or not.
+	///
+	/// Segments are only locked by VIPs, either by submitting or upvoting
+	/// them. There's no threshold of community votes that locks a segment, so
+	/// a locked segment has always been approved by a VIP.
 	pub locked: bool,
 	/// How many votes the segment has.
 	pub votes: i32,
@@ -51,6 +86,160 @@ pub struct Segment {
 }
 
 impl Segment {
+	/// Creates a new segment, such as for testing or for combining segments
+	/// from the API with ones from elsewhere.
+	///
+	/// The optional fields, [`video_duration_on_submission`] and
+	/// [`additional_info`], start out as [`None`]. Since all fields are public,
+	/// they can be set directly afterwards. [`AdditionalSegmentInfo`] can be
+	/// created with [`AdditionalSegmentInfo::new`] and filled in the same way.
+	///
+	/// [`video_duration_on_submission`]: Self::video_duration_on_submission
+	/// [`additional_info`]: Self::additional_info
+	#[must_use]
+	pub fn new<U>(category: Category, action: Action, uuid: U, locked: bool, votes: i32) -> Self
+	where
+		U: Into<String>,
+	{
+		Self {
+			category,
+			action,
+			uuid: uuid.into(),
+			locked,
+			votes,
+			video_duration_on_submission: None,
+			additional_info: None,
+		}
+	}
+
+	/// Gets the kind of action the segment has, without any of the time
+	/// information.
+	#[must_use]
+	pub fn action_kind(&self) -> ActionKind {
+		ActionKind::from(&self.action)
+	}
+
+	/// Translates the segment's [`action`] into a concrete instruction for a
+	/// player, following the submitter's recommendation.
+	///
+	/// This doesn't account for user preferences - see [`should_skip`] for
+	/// that.
+	///
+	/// [`action`]: Self::action
+	#[must_use]
+	pub fn recommended_player_action(&self) -> PlayerAction {
+		match self.action {
+			Action::Skip(_, end) => PlayerAction::SeekTo(end),
+			Action::Mute(start, end) => PlayerAction::MuteRange(start, end),
+			Action::PointOfInterest(point) => PlayerAction::ShowMarker(point),
+			Action::FullVideo => PlayerAction::WholeVideoLabel,
+		}
+	}
+
+	/// Creates a copy of the segment with its times rounded to a number of
+	/// decimal places.
+	///
+	/// The API returns times with more precision than is useful, so this is
+	/// intended for display and for comparing segments between tools. The
+	/// rounded times shouldn't be used for actually skipping, since they may
+	/// fall slightly inside or outside the real segment.
+	///
+	/// Only the times in [`action`] are rounded.
+	///
+	/// [`action`]: Self::action
+	#[must_use]
+	pub fn rounded(&self, decimals: u32) -> Self {
+		let factor =
+			i32::try_from(decimals).map_or(f32::INFINITY, |decimals| 10_f32.powi(decimals));
+		let round = |time: f32| {
+			let scaled = time * factor;
+			// Scaling only overflows with far more decimal places than an `f32`
+			// can hold, in which case rounding wouldn't change anything
+			if scaled.is_finite() {
+				scaled.round() / factor
+			} else {
+				time
+			}
+		};
+
+		Self {
+			action: match self.action {
+				Action::Skip(start, end) => Action::Skip(round(start), round(end)),
+				Action::Mute(start, end) => Action::Mute(round(start), round(end)),
+				Action::PointOfInterest(point) => Action::PointOfInterest(round(point)),
+				Action::FullVideo => Action::FullVideo,
+			},
+			..self.clone()
+		}
+	}
+
+	/// Whether the segment has more downvotes than upvotes.
+	#[must_use]
+	pub fn is_downvoted(&self) -> bool {
+		self.votes < 0
+	}
+
+	/// A rough score from `0.0` to `1.0` of how trustworthy the segment is,
+	/// based on its votes and views.
+	///
+	/// Locked segments always have a confidence of `1.0`. Otherwise, the
+	/// score is `s / (s + 2)`, where
+	/// `s = max(votes + 2, 0) + log10(views + 1)`. This means a new segment
+	/// with no votes or views has a confidence of `0.5`, and segments with `-2`
+	/// votes or fewer (which the API hides) and no views have a confidence of
+	/// `0.0`. Views count for much less than votes, since every skip counts as
+	/// a view.
+	///
+	/// Views rely on [`AdditionalSegmentInfo::views`], so segments without
+	/// additional info are scored as if they have no views.
+	#[must_use]
+	pub fn confidence(&self) -> f32 {
+		if self.locked {
+			return 1.0;
+		}
+
+		let views = self.additional_info.as_ref().map_or(0, |info| info.views);
+		let score = (self.votes as f32 + 2.0).max(0.0) + (views as f32 + 1.0).log10();
+		score / (score + 2.0)
+	}
+
+	/// Compares two segments by their number of votes.
+	///
+	/// This is intended for use with sorting functions like
+	/// [`slice::sort_by`]. To sort the most trusted segments first, reverse the
+	/// result.
+	#[must_use]
+	pub fn cmp_by_votes(a: &Self, b: &Self) -> Ordering {
+		a.votes.cmp(&b.votes)
+	}
+
+	/// Compares two segments by their number of views.
+	///
+	/// This relies on [`AdditionalSegmentInfo::views`], so segments without
+	/// additional info are ordered before all those that have it.
+	///
+	/// This is intended for use with sorting functions like
+	/// [`slice::sort_by`]. To sort the most viewed segments first, reverse the
+	/// result.
+	#[must_use]
+	pub fn cmp_by_views(a: &Self, b: &Self) -> Ordering {
+		let views = |segment: &Self| segment.additional_info.as_ref().map(|info| info.views);
+		views(a).cmp(&views(b))
+	}
+
+	/// Compares two segments by their start time.
+	///
+	/// Full-video labels don't have a start time, so they're ordered before
+	/// all other segments.
+	///
+	/// This is intended for use with sorting functions like
+	/// [`slice::sort_by`].
+	#[must_use]
+	pub fn cmp_by_start_time(a: &Self, b: &Self) -> Ordering {
+		let start = |segment: &Self| segment.action.start_time();
+		start(a).partial_cmp(&start(b)).unwrap_or(Ordering::Equal)
+	}
+
 	/// Fetches the additional information for the segment, filling in the
 	/// [`additional_info`] field.
 	///
@@ -83,7 +272,7 @@ impl Segment {
 ///
 /// Whether or not a function supplies this information will be
 /// noted in its documentation.
-#[derive(Clone, Debug, Deserialize, PartialEq, PartialOrd)]
+#[derive(Clone, Debug, Deserialize, PartialEq, PartialOrd, Serialize)]
 #[non_exhaustive]
 #[serde(default, rename_all = "camelCase")]
 pub struct AdditionalSegmentInfo {
@@ -96,32 +285,74 @@ pub struct AdditionalSegmentInfo {
 	#[serde(rename = "userID")]
 	pub submitter_id: String,
 	/// The date and time that the segment was submitted.
-	#[serde(deserialize_with = "datetime_from_millis_timestamp")]
+	#[serde(
+		deserialize_with = "datetime_from_millis_timestamp",
+		serialize_with = "datetime_to_millis_timestamp"
+	)]
 	pub time_submitted: OffsetDateTime,
 	/// The number of views the segment has.
 	pub views: u32,
 	/// The service the segment is associated with.
 	pub service: String,
 	/// Whether or not the segment is hidden.
-	#[serde(deserialize_with = "bool_from_integer_str")]
+	#[serde(
+		deserialize_with = "bool_from_integer_str",
+		serialize_with = "bool_to_integer"
+	)]
 	pub hidden: bool,
 	/// The reputation of the submitter upon submission of the segment.
 	pub submitter_reputation: f32,
 	/// Whether or not the submitter is shadow-banned.
-	#[serde(deserialize_with = "bool_from_integer_str")]
+	#[serde(
+		deserialize_with = "bool_from_integer_str",
+		serialize_with = "bool_to_integer"
+	)]
 	pub shadow_banned: bool,
 	/// The user agent string of the submitter upon submission.
 	pub submitter_user_agent: String,
 }
 
+impl AdditionalSegmentInfo {
+	/// Creates new additional segment information, such as for testing code
+	/// that works with segments fetched with their additional info.
+	///
+	/// The rest of the fields start out with their default values - no views,
+	/// not hidden, not shadow-banned, and so on. Since all fields are public,
+	/// they can be set directly afterwards.
+	#[must_use]
+	pub fn new<V, U>(video_id: V, submitter_id: U, time_submitted: OffsetDateTime) -> Self
+	where
+		V: Into<String>,
+		U: Into<String>,
+	{
+		Self {
+			video_id: video_id.into(),
+			submitter_id: submitter_id.into(),
+			time_submitted,
+			..Self::default()
+		}
+	}
+
+	/// The date and time that the segment was submitted, as a [`SystemTime`].
+	///
+	/// This is the same as [`time_submitted`], for use without the `time`
+	/// crate.
+	///
+	/// [`time_submitted`]: Self::time_submitted
+	#[must_use]
+	pub fn time_submitted_system(&self) -> SystemTime {
+		self.time_submitted.into()
+	}
+}
+
 impl Default for AdditionalSegmentInfo {
 	fn default() -> Self {
 		Self {
 			video_id: String::default(),
 			incorrect_votes: u32::default(),
 			submitter_id: String::default(),
-			time_submitted: OffsetDateTime::UNIX_EPOCH, /* Not great, but this should in theory
-			                                             * never be called */
+			time_submitted: OffsetDateTime::UNIX_EPOCH, /* Not great, but there's no better
+			                                             * placeholder */
 			views: u32::default(),
 			service: String::default(),
 			hidden: bool::default(),
@@ -131,3 +362,57 @@ impl Default for AdditionalSegmentInfo {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	// Uses
+	use time::OffsetDateTime;
+
+	use super::{Action, AdditionalSegmentInfo, Category, Segment};
+
+	fn segment(votes: i32, views: Option<u32>, locked: bool) -> Segment {
+		Segment {
+			category: Category::Sponsor,
+			action: Action::Skip(1.0, 2.0),
+			uuid: String::new(),
+			locked,
+			votes,
+			video_duration_on_submission: None,
+			additional_info: views.map(|views| AdditionalSegmentInfo {
+				views,
+				..AdditionalSegmentInfo::new("dQw4w9WgXcQ", "", OffsetDateTime::UNIX_EPOCH)
+			}),
+		}
+	}
+
+	#[test]
+	fn confidence() {
+		// (votes, views, locked, expected confidence)
+		let cases = [
+			// New segments
+			(0, None, false, 0.5),
+			(0, Some(0), false, 0.5),
+			// Hidden segments without views
+			(-2, Some(0), false, 0.0),
+			(-5, None, false, 0.0),
+			// Hidden segments only get credit for their views
+			(-2, Some(99), false, 0.5),
+			// Votes and views both count
+			(2, Some(0), false, 4.0 / 6.0),
+			(0, Some(9), false, 3.0 / 5.0),
+			(2, Some(99), false, 6.0 / 8.0),
+			// Locked segments are always trusted
+			(0, None, true, 1.0),
+			(-5, Some(0), true, 1.0),
+		];
+
+		for (votes, views, locked, expected) in cases {
+			let confidence = segment(votes, views, locked).confidence();
+			assert!(
+				(confidence - expected).abs() < 1e-6,
+				"votes: {votes}, views: {views:?}, locked: {locked}: expected {expected}, got \
+				 {confidence}"
+			);
+		}
+	}
+}
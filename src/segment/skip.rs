@@ -0,0 +1,265 @@
+//! Functions for deciding how players should treat segments.
+
+// Uses
+use std::collections::HashMap;
+
+use crate::{AcceptedCategories, Action, Category, Segment};
+
+/// How a user wants segments of a category to be treated.
+///
+/// See [`CategoryPrefs`] for more information.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum CategoryPreference {
+	/// Automatically skip segments of the category.
+	Skip,
+	/// Automatically mute segments of the category instead of skipping them.
+	Mute,
+	/// Show segments of the category, but don't act on them automatically.
+	ShowOnly,
+	/// Ignore segments of the category entirely.
+	Disabled,
+}
+
+/// A user's preferences for how each category of segment should be treated.
+///
+/// Categories without an explicit preference fall back to a default based on
+/// the category: [`CategoryPreference::Skip`] if it's
+/// [skippable](Category::is_skippable), and [`CategoryPreference::ShowOnly`]
+/// otherwise.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CategoryPrefs {
+	preferences: HashMap<Category, CategoryPreference>,
+}
+
+impl CategoryPrefs {
+	/// Creates a new instance of the struct, with no explicit preferences.
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets the preference for a category.
+	pub fn set(&mut self, category: Category, preference: CategoryPreference) -> &mut Self {
+		self.preferences.insert(category, preference);
+		self
+	}
+
+	/// Gets the preference for a category, falling back to the default if none
+	/// was set.
+	#[must_use]
+	pub fn get(&self, category: Category) -> CategoryPreference {
+		self.preferences.get(&category).copied().unwrap_or_else(|| {
+			if category.is_skippable() {
+				CategoryPreference::Skip
+			} else {
+				CategoryPreference::ShowOnly
+			}
+		})
+	}
+
+	/// Gets the categories that aren't [`Disabled`], for fetching only the
+	/// segments that the preferences care about.
+	///
+	/// [`Disabled`]: CategoryPreference::Disabled
+	#[must_use]
+	pub fn accepted_categories(&self) -> AcceptedCategories {
+		AcceptedCategories::all()
+			.iter()
+			.filter(|&category| self.get(category) != CategoryPreference::Disabled)
+			.collect()
+	}
+}
+
+/// What a player should do with a segment, as resolved by [`should_skip`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum SkipBehavior {
+	/// Skip past the segment.
+	Skip,
+	/// Mute the audio for the duration of the segment.
+	Mute,
+	/// Show the segment to the user, but don't act on it.
+	Show,
+	/// Don't do anything with the segment.
+	Ignore,
+}
+
+/// A concrete instruction for a player, as given by
+/// [`Segment::recommended_player_action`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum PlayerAction {
+	/// Once playback reaches the start of the segment, seek to the contained
+	/// time, which is the end of the segment.
+	SeekTo(f32),
+	/// Mute the audio between the contained start and end times.
+	MuteRange(f32, f32),
+	/// Show a marker at the contained time, such as for a highlight.
+	ShowMarker(f32),
+	/// Label the whole video, without changing playback.
+	WholeVideoLabel,
+}
+
+/// Decides what a player should do with a segment, based on the user's
+/// preferences.
+///
+/// The decision is made as follows:
+/// 1. If the category is [`Disabled`], the segment is ignored.
+/// 2. If the category is [`ShowOnly`], or the segment can't be skipped or muted
+///    (points, full video labels, and categories that aren't
+///    [skippable](Category::is_skippable)), the segment is shown.
+/// 3. Segments the submitter marked to be muted are muted, even if the category
+///    is set to [`Skip`], since skipping them would cut out content.
+/// 4. Otherwise, the category preference is followed.
+///
+/// [`Disabled`]: CategoryPreference::Disabled
+/// [`ShowOnly`]: CategoryPreference::ShowOnly
+/// [`Skip`]: CategoryPreference::Skip
+#[must_use]
+pub fn should_skip(segment: &Segment, prefs: &CategoryPrefs) -> SkipBehavior {
+	let preference = prefs.get(segment.category);
+	if preference == CategoryPreference::Disabled {
+		return SkipBehavior::Ignore;
+	}
+	if preference == CategoryPreference::ShowOnly || !segment.category.is_skippable() {
+		return SkipBehavior::Show;
+	}
+
+	match segment.action {
+		Action::Skip(..) => match preference {
+			CategoryPreference::Skip => SkipBehavior::Skip,
+			CategoryPreference::Mute => SkipBehavior::Mute,
+			CategoryPreference::ShowOnly | CategoryPreference::Disabled => SkipBehavior::Show,
+		},
+		Action::Mute(..) => SkipBehavior::Mute,
+		Action::PointOfInterest(_) | Action::FullVideo => SkipBehavior::Show,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	// Uses
+	use super::{should_skip, CategoryPreference, CategoryPrefs, SkipBehavior};
+	use crate::{Action, Category, Segment};
+
+	fn segment(category: Category, action: Action) -> Segment {
+		Segment {
+			category,
+			action,
+			uuid: String::new(),
+			locked: false,
+			votes: 0,
+			video_duration_on_submission: None,
+			additional_info: None,
+		}
+	}
+
+	#[test]
+	fn should_skip_decisions() {
+		let skip = Action::Skip(1.0, 2.0);
+		let mute = Action::Mute(1.0, 2.0);
+
+		// (category, action, preference, expected behaviour)
+		let cases = [
+			// Disabled categories are ignored, whatever the action
+			(
+				Category::Sponsor,
+				skip,
+				Some(CategoryPreference::Disabled),
+				SkipBehavior::Ignore,
+			),
+			(
+				Category::Highlight,
+				Action::PointOfInterest(1.0),
+				Some(CategoryPreference::Disabled),
+				SkipBehavior::Ignore,
+			),
+			// Show-only categories are shown
+			(
+				Category::Sponsor,
+				skip,
+				Some(CategoryPreference::ShowOnly),
+				SkipBehavior::Show,
+			),
+			(
+				Category::Sponsor,
+				mute,
+				Some(CategoryPreference::ShowOnly),
+				SkipBehavior::Show,
+			),
+			// Categories that aren't skippable are shown, even if set to skip
+			(
+				Category::ExclusiveAccess,
+				Action::FullVideo,
+				Some(CategoryPreference::Skip),
+				SkipBehavior::Show,
+			),
+			(
+				Category::ExclusiveAccess,
+				skip,
+				Some(CategoryPreference::Skip),
+				SkipBehavior::Show,
+			),
+			// Skip segments follow the preference
+			(
+				Category::Sponsor,
+				skip,
+				Some(CategoryPreference::Skip),
+				SkipBehavior::Skip,
+			),
+			(
+				Category::Sponsor,
+				skip,
+				Some(CategoryPreference::Mute),
+				SkipBehavior::Mute,
+			),
+			// Mute segments are never skipped
+			(
+				Category::Sponsor,
+				mute,
+				Some(CategoryPreference::Skip),
+				SkipBehavior::Mute,
+			),
+			(
+				Category::Sponsor,
+				mute,
+				Some(CategoryPreference::Mute),
+				SkipBehavior::Mute,
+			),
+			// Points and full video labels can't be skipped
+			(
+				Category::Sponsor,
+				Action::PointOfInterest(1.0),
+				Some(CategoryPreference::Skip),
+				SkipBehavior::Show,
+			),
+			(
+				Category::Sponsor,
+				Action::FullVideo,
+				Some(CategoryPreference::Skip),
+				SkipBehavior::Show,
+			),
+			(
+				Category::Highlight,
+				Action::PointOfInterest(1.0),
+				None,
+				SkipBehavior::Show,
+			),
+			// Skippable categories default to being skipped
+			(Category::Sponsor, skip, None, SkipBehavior::Skip),
+		];
+
+		for (category, action, preference, expected) in cases {
+			let mut prefs = CategoryPrefs::new();
+			if let Some(preference) = preference {
+				prefs.set(category, preference);
+			}
+			assert_eq!(
+				should_skip(&segment(category, action), &prefs),
+				expected,
+				"category: {category:?}, action: {action:?}, preference: {preference:?}"
+			);
+		}
+	}
+}
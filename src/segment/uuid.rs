@@ -0,0 +1,78 @@
+//! Utilities for working with segment UUIDs.
+
+/// Checks whether a string has the shape of a segment UUID.
+///
+/// Segments submitted today have a UUID of 64 hexadecimal digits followed by a
+/// single version digit, while older ones have 64 hexadecimal digits alone, or
+/// a standard hyphenated UUID like `123e4567-e89b-12d3-a456-426614174000`.
+/// Anything else can't be a segment UUID, so there's no point asking the API
+/// about it.
+///
+/// This only checks the format, not whether a segment with the UUID exists.
+#[must_use]
+pub fn validate_segment_uuid<U>(uuid: U) -> bool
+where
+	U: AsRef<str>,
+{
+	const HASH_LENGTH: usize = 64;
+	const HYPHEN_POSITIONS: [usize; 4] = [8, 13, 18, 23];
+	const HYPHENATED_LENGTH: usize = 36;
+	const VERSIONED_HASH_LENGTH: usize = HASH_LENGTH + 1;
+
+	let uuid = uuid.as_ref();
+	match uuid.len() {
+		HASH_LENGTH | VERSIONED_HASH_LENGTH => uuid.bytes().all(|b| b.is_ascii_hexdigit()),
+		HYPHENATED_LENGTH => uuid.bytes().enumerate().all(|(i, b)| {
+			if HYPHEN_POSITIONS.contains(&i) {
+				b == b'-'
+			} else {
+				b.is_ascii_hexdigit()
+			}
+		}),
+		_ => false,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	// Uses
+	use super::validate_segment_uuid;
+
+	#[test]
+	fn validate_segment_uuid_forms() {
+		let hash = "a".repeat(64);
+		let hash_with_version = format!("{hash}7");
+		let cases = [
+			// Accepted
+			(hash.clone(), true),
+			(
+				"0123456789abcdefABCDEF0123456789abcdefABCDEF0123456789abcdef0123".to_owned(),
+				true,
+			),
+			(hash_with_version.clone(), true),
+			("123e4567-e89b-12d3-a456-426614174000".to_owned(), true),
+			("123E4567-E89B-12D3-A456-426614174000".to_owned(), true),
+			// Wrong lengths
+			(String::new(), false),
+			("a".repeat(63), false),
+			("a".repeat(66), false),
+			("123e4567-e89b-12d3-a456-42661417400".to_owned(), false),
+			// Non-hexadecimal characters
+			(format!("{}g", "a".repeat(63)), false),
+			(format!("{hash}g"), false),
+			(format!(" {}", "a".repeat(63)), false),
+			("123e4567-e89b-12d3-a456-42661417400z".to_owned(), false),
+			// Hyphens out of place
+			("123e4567e-89b-12d3-a456-426614174000".to_owned(), false),
+			("123e4567-e89b-12d3-a456-4266-4174000".to_owned(), false),
+			("123e4567-e89b-12d3-a45642-6614174000".to_owned(), false),
+			(format!("{}-", "a".repeat(64)), false),
+			// Non-ASCII characters that happen to fit the byte length
+			(format!("{}é", "a".repeat(34)), false),
+		];
+
+		for (uuid, expected) in cases {
+			assert_eq!(validate_segment_uuid(&uuid), expected, "{uuid:?}");
+		}
+	}
+}
@@ -0,0 +1,205 @@
+// Uses
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::{
+	ffi::OsString,
+	fs::{self, OpenOptions},
+	io::{self, ErrorKind, Write},
+	path::{Path, PathBuf},
+};
+
+use crate::gen_user_id;
+
+/// A utility function that loads a local user ID from a file, or generates and
+/// saves a new one if there isn't one yet.
+///
+/// This codifies the recommended way of using local user IDs: generate one
+/// once, then keep using it across sessions. The ID should be treated like a
+/// password, so on Unix platforms new files are only readable and writable by
+/// their owner.
+///
+/// Surrounding whitespace in the file is ignored, and an empty file is treated
+/// the same as a missing one. If multiple processes try to create the file at
+/// the same time, they all end up with the same ID. This relies on hard links,
+/// though - on filesystems that don't support them, or if the file exists but
+/// is empty, the new ID replaces the file instead, so concurrent callers may
+/// each return a different ID and the last one to finish is the one that's
+/// kept.
+///
+/// # Errors
+/// Returns any I/O error encountered while reading or writing the file.
+pub fn load_or_generate_user_id<P>(path: P) -> io::Result<String>
+where
+	P: AsRef<Path>,
+{
+	let path = path.as_ref();
+
+	// Use the existing ID if there is one
+	if let Some(user_id) = read_user_id(path)? {
+		return Ok(user_id);
+	}
+
+	// Otherwise, write a new one to a temporary file and move it into place
+	// atomically, so that concurrent callers never see a partially-written ID
+	let user_id = gen_user_id();
+	let temp_path = temp_path_for(path);
+	let mut temp_file = {
+		let mut options = OpenOptions::new();
+		options.write(true).create_new(true);
+		#[cfg(unix)]
+		options.mode(0o600);
+		options.open(&temp_path)?
+	};
+	let write_result = temp_file
+		.write_all(user_id.as_bytes())
+		.and_then(|()| temp_file.sync_all());
+	drop(temp_file);
+	if let Err(error) = write_result {
+		let _ = fs::remove_file(&temp_path);
+		return Err(error);
+	}
+
+	// Hard-linking fails if the file was created in the meantime, unlike renaming
+	let result = match fs::hard_link(&temp_path, path) {
+		Ok(()) => Ok(user_id),
+		// The temporary file still has to be cleaned up if this fails, so the error is
+		// returned below instead of right away
+		Err(error) if error.kind() == ErrorKind::AlreadyExists => match read_user_id(path) {
+			Ok(Some(existing_user_id)) => Ok(existing_user_id),
+			// The file exists but is empty, so replace it - if another caller is doing the
+			// same, the last one to rename wins
+			Ok(None) => fs::rename(&temp_path, path).map(|()| user_id),
+			Err(error) => Err(error),
+		},
+		// Some filesystems don't support hard links, and renaming can't tell whether
+		// another caller created the file in the meantime, so the last one to rename wins
+		Err(_) => fs::rename(&temp_path, path).map(|()| user_id),
+	};
+	let _ = fs::remove_file(&temp_path);
+
+	result
+}
+
+/// Reads a user ID from a file, returning [`None`] if the file doesn't exist or
+/// is empty.
+fn read_user_id(path: &Path) -> io::Result<Option<String>> {
+	match fs::read_to_string(path) {
+		Ok(contents) => {
+			let user_id = contents.trim();
+			Ok((!user_id.is_empty()).then(|| user_id.to_owned()))
+		}
+		Err(error) if error.kind() == ErrorKind::NotFound => Ok(None),
+		Err(error) => Err(error),
+	}
+}
+
+/// Builds a unique temporary file path next to the provided one.
+fn temp_path_for(path: &Path) -> PathBuf {
+	let mut file_name = path.file_name().map_or_else(OsString::new, OsString::from);
+	file_name.push(format!(".{}.tmp", &gen_user_id()[..8]));
+	path.with_file_name(file_name)
+}
+
+#[cfg(test)]
+mod tests {
+	// Uses
+	use std::{
+		env,
+		fs,
+		path::{Path, PathBuf},
+		sync::{Arc, Barrier},
+		thread,
+	};
+
+	use super::load_or_generate_user_id;
+	use crate::gen_user_id;
+
+	/// A temporary directory that's removed when dropped.
+	struct TempDir(PathBuf);
+
+	impl TempDir {
+		fn new() -> Self {
+			let path = env::temp_dir().join(format!("sponsor-block-test-{}", gen_user_id()));
+			fs::create_dir(&path).unwrap();
+			Self(path)
+		}
+
+		fn path(&self) -> &Path {
+			&self.0
+		}
+
+		/// Gets the names of the files in the directory, to check that no
+		/// temporary files are left behind.
+		fn file_names(&self) -> Vec<String> {
+			fs::read_dir(&self.0)
+				.unwrap()
+				.map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+				.collect()
+		}
+	}
+
+	impl Drop for TempDir {
+		fn drop(&mut self) {
+			let _ = fs::remove_dir_all(&self.0);
+		}
+	}
+
+	#[test]
+	fn generates_and_saves_a_missing_id() {
+		let dir = TempDir::new();
+		let path = dir.path().join("user_id");
+
+		let user_id = load_or_generate_user_id(&path).unwrap();
+		assert_eq!(fs::read_to_string(&path).unwrap(), user_id);
+		assert_eq!(load_or_generate_user_id(&path).unwrap(), user_id);
+		assert_eq!(dir.file_names(), ["user_id"]);
+	}
+
+	#[test]
+	fn replaces_an_empty_file() {
+		let dir = TempDir::new();
+		let path = dir.path().join("user_id");
+		fs::write(&path, " \n").unwrap();
+
+		let user_id = load_or_generate_user_id(&path).unwrap();
+		assert!(!user_id.is_empty());
+		assert_eq!(fs::read_to_string(&path).unwrap(), user_id);
+		assert_eq!(dir.file_names(), ["user_id"]);
+	}
+
+	#[test]
+	fn trims_surrounding_whitespace() {
+		let dir = TempDir::new();
+		let path = dir.path().join("user_id");
+		fs::write(&path, "  existing-id\r\n").unwrap();
+
+		assert_eq!(load_or_generate_user_id(&path).unwrap(), "existing-id");
+	}
+
+	#[test]
+	fn concurrent_callers_get_the_same_id() {
+		const THREAD_COUNT: usize = 8;
+
+		let dir = TempDir::new();
+		let path = Arc::new(dir.path().join("user_id"));
+		let barrier = Arc::new(Barrier::new(THREAD_COUNT));
+
+		let user_ids = (0..THREAD_COUNT)
+			.map(|_| {
+				let path = Arc::clone(&path);
+				let barrier = Arc::clone(&barrier);
+				thread::spawn(move || {
+					barrier.wait();
+					load_or_generate_user_id(path.as_path()).unwrap()
+				})
+			})
+			.collect::<Vec<_>>()
+			.into_iter()
+			.map(|handle| handle.join().unwrap())
+			.collect::<Vec<_>>();
+
+		assert!(user_ids.iter().all(|user_id| *user_id == user_ids[0]));
+		assert_eq!(fs::read_to_string(path.as_path()).unwrap(), user_ids[0]);
+		assert_eq!(dir.file_names(), ["user_id"]);
+	}
+}
@@ -4,15 +4,44 @@
 // Uses
 use std::fmt::Write;
 
-use reqwest::Response;
+use reqwest::{header::CONTENT_TYPE, Response};
+use serde::de::{DeserializeOwned, Error as DeError};
+use serde_json::{Deserializer as JsonDeserializer, Error as JsonError};
 
 use crate::error::{Result, SponsorBlockError};
 
+/// How many characters of an unexpected response to include in the error.
+const UNEXPECTED_RESPONSE_PREVIEW_LENGTH: usize = 200;
+
 /// Parses the [`Response`] and categorizes errors depending on their source.
-pub(crate) async fn get_response_text(response: Response) -> Result<String> {
+///
+/// If `max_bytes` is provided, reading stops with an error once the body
+/// exceeds it.
+pub(crate) async fn get_response_text(
+	response: Response,
+	max_bytes: Option<usize>,
+) -> Result<String> {
 	let status = response.status();
 	if status.is_success() {
-		Ok(response.text().await?)
+		let content_type = response
+			.headers()
+			.get(CONTENT_TYPE)
+			.and_then(|value| value.to_str().ok())
+			.map(str::to_owned);
+		let text = match max_bytes {
+			Some(max_bytes) => read_limited_text(response, max_bytes).await?,
+			None => response.text().await?,
+		};
+
+		if is_html_response(content_type.as_deref(), text.as_str()) {
+			return Err(SponsorBlockError::UnexpectedResponse(
+				text.chars()
+					.take(UNEXPECTED_RESPONSE_PREVIEW_LENGTH)
+					.collect(),
+			));
+		}
+
+		Ok(text)
 	} else if status.is_server_error() {
 		Err(SponsorBlockError::HttpApi(status.as_u16()))
 	} else if status.is_client_error() {
@@ -22,59 +51,103 @@ pub(crate) async fn get_response_text(response: Response) -> Result<String> {
 	}
 }
 
-pub(crate) fn to_url_array<S>(slice: &[S]) -> String
-where
-	S: AsRef<str>,
-{
-	to_url_array_conditional(slice, |_| true)
+/// Checks whether a successful response is an HTML page rather than the JSON
+/// the API sends.
+///
+/// Proxies in front of the API sometimes answer with an HTML page instead, and
+/// don't always label it as such.
+fn is_html_response(content_type: Option<&str>, text: &str) -> bool {
+	content_type.is_some_and(|content_type| content_type.contains("html"))
+		|| text.trim_start().starts_with('<')
+}
+
+/// Reads the body of a [`Response`] as text, failing if it exceeds `max_bytes`.
+async fn read_limited_text(mut response: Response, max_bytes: usize) -> Result<String> {
+	let too_large = || SponsorBlockError::ResponseTooLarge(max_bytes);
+
+	// Bail out early if the server says up front that the body is too large
+	if response
+		.content_length()
+		.is_some_and(|length| length > max_bytes as u64)
+	{
+		return Err(too_large());
+	}
+
+	let mut body = Vec::new();
+	while let Some(chunk) = response.chunk().await? {
+		if body.len() + chunk.len() > max_bytes {
+			return Err(too_large());
+		}
+		body.extend_from_slice(&chunk);
+	}
+
+	Ok(String::from_utf8_lossy(&body).into_owned())
 }
 
-pub(crate) fn to_url_array_conditional<S, P>(slice: &[S], predicate: P) -> String
+/// Deserializes JSON text received from the API.
+///
+/// If the data doesn't match the expected structure, the text is deserialized
+/// a second time to find the path to the offending value, which is included in
+/// the error message. This makes it much easier to figure out what changed
+/// when the API gets out of sync with the library.
+pub(crate) fn from_json_str<T>(text: &str) -> Result<T>
 where
-	S: AsRef<str>,
-	P: Fn(&S) -> bool,
+	T: DeserializeOwned,
 {
-	to_url_array_conditional_convert(slice, predicate, |s| s)
+	serde_json::from_str(text).map_err(|error| {
+		if !error.is_data() {
+			return error.into();
+		}
+
+		let mut deserializer = JsonDeserializer::from_str(text);
+		match serde_path_to_error::deserialize::<_, T>(&mut deserializer) {
+			Err(path_error) => {
+				JsonError::custom(format!("{}: {}", path_error.path(), path_error.inner())).into()
+			}
+			Ok(_) => error.into(),
+		}
+	})
 }
 
-pub(crate) fn to_url_array_conditional_convert<'e, E, S, P, C>(
-	slice: &'e [E],
-	predicate: P,
-	convert: C,
-) -> String
+pub(crate) fn to_url_array<I>(items: I) -> String
 where
-	S: AsRef<str>,
-	P: Fn(&'e E) -> bool,
-	C: Fn(&'e E) -> S,
+	I: IntoIterator,
+	I::Item: AsRef<str>,
 {
 	let mut result = String::from('[');
 
-	let mut pushed_already = false;
-	for s in slice.iter() {
-		if !predicate(s) {
-			continue;
-		}
-
-		if pushed_already {
+	for (i, item) in items.into_iter().enumerate() {
+		if i > 0 {
 			result.push(',');
 		}
 
 		result.push('"');
-		result.push_str(convert(s).as_ref());
+		result.push_str(item.as_ref());
 		result.push('"');
-
-		pushed_already = true;
 	}
 
 	result.push(']');
 	result
 }
 
+pub(crate) fn to_url_array_conditional_convert<'e, E, S, P, C>(
+	slice: &'e [E],
+	predicate: P,
+	convert: C,
+) -> String
+where
+	S: AsRef<str>,
+	P: Fn(&'e E) -> bool,
+	C: Fn(&'e E) -> S,
+{
+	to_url_array(slice.iter().filter(|&e| predicate(e)).map(convert))
+}
+
 #[cfg(feature = "private_searches")]
 pub(crate) fn bytes_to_hex_string(bytes: &[u8]) -> String {
 	let mut result = String::with_capacity(bytes.len() * 2);
 	for byte in bytes {
-		write!(result, "{:02x}", byte).expect("unable to write byte to string");
+		write!(result, "{byte:02x}").expect("unable to write byte to string");
 	}
 	result
 }
@@ -92,24 +165,90 @@ pub(crate) mod de {
 	///
 	/// Many API fields that are boolean in nature use an integer
 	/// representation, which is why.
+	///
+	/// Actual booleans and `null` (as `false`) are also accepted, since not
+	/// every instance of the API is consistent about this.
 	pub(crate) fn bool_from_integer_str<'de, D>(deserializer: D) -> StdResult<bool, D::Error>
 	where
 		D: Deserializer<'de>,
 	{
-		let raw = isize::deserialize(deserializer)?;
-		Ok(raw != 0)
+		#[derive(Deserialize)]
+		#[serde(untagged)]
+		enum Raw {
+			Integer(isize),
+			Bool(bool),
+		}
+
+		Ok(match Option::<Raw>::deserialize(deserializer)? {
+			Some(Raw::Integer(raw)) => raw != 0,
+			Some(Raw::Bool(raw)) => raw,
+			None => false,
+		})
+	}
+
+	/// A custom deserializer that accepts an `f32` value given as either a
+	/// number or a numeric string.
+	///
+	/// Some mirrors and older versions of the API return times as strings.
+	pub(crate) fn f32_from_number_or_str<'de, D>(deserializer: D) -> StdResult<f32, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		#[derive(Deserialize)]
+		#[serde(untagged)]
+		enum Raw {
+			Number(f32),
+			String(String),
+		}
+
+		match Raw::deserialize(deserializer)? {
+			Raw::Number(number) => Ok(number),
+			Raw::String(string) => string.trim().parse().map_err(D::Error::custom),
+		}
+	}
+
+	/// An `f32` value that may be given as either a number or a numeric string.
+	///
+	/// See [`f32_from_number_or_str`] for more information.
+	#[derive(Deserialize)]
+	struct LenientF32(#[serde(deserialize_with = "f32_from_number_or_str")] f32);
+
+	/// A custom deserializer for an optional `f32` value that may be given as
+	/// either a number or a numeric string.
+	///
+	/// See [`f32_from_number_or_str`] for more information.
+	pub(crate) fn option_f32_from_number_or_str<'de, D>(
+		deserializer: D,
+	) -> StdResult<Option<f32>, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		Ok(Option::<LenientF32>::deserialize(deserializer)?.map(|value| value.0))
+	}
+
+	/// A custom deserializer for an optional pair of `f32` values that may each
+	/// be given as either a number or a numeric string.
+	///
+	/// See [`f32_from_number_or_str`] for more information.
+	pub(crate) fn option_f32_pair_from_number_or_str<'de, D>(
+		deserializer: D,
+	) -> StdResult<Option<[f32; 2]>, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		Ok(Option::<[LenientF32; 2]>::deserialize(deserializer)?.map(|[a, b]| [a.0, b.0]))
 	}
 
 	/// A custom deserializer that maps an `f32` value to `None` if it's `0.0`.
 	///
 	/// This is because `f32` segments submitted before a field was added
-	/// default to `0.0`.
+	/// default to `0.0`. `null` is treated the same way.
 	pub(crate) fn none_on_0_0_from_str<'de, D>(deserializer: D) -> StdResult<Option<f32>, D::Error>
 	where
 		D: Deserializer<'de>,
 	{
-		let raw = f32::deserialize(deserializer)?;
-		Ok(if raw == 0.0 { None } else { Some(raw) })
+		let raw = Option::<f32>::deserialize(deserializer)?;
+		Ok(raw.filter(|&raw| raw != 0.0))
 	}
 
 	/// A custom deserializer that maps a [`HashMap`]'s keys using an arbitrary
@@ -177,3 +316,171 @@ pub(crate) mod de {
 			.map_err(D::Error::custom)
 	}
 }
+
+/// For all serialization helper functions.
+///
+/// These are the counterparts of the ones in [`de`], so that data can be
+/// serialized in the same format the API uses.
+pub(crate) mod ser {
+	// Uses
+	use std::result::Result as StdResult;
+
+	use serde::{ser::Error, Serializer};
+	use time::OffsetDateTime;
+
+	/// A custom serializer that maps a boolean value to `1` or `0`.
+	///
+	/// This is the counterpart of [`bool_from_integer_str`].
+	///
+	/// [`bool_from_integer_str`]: super::de::bool_from_integer_str
+	#[allow(clippy::trivially_copy_pass_by_ref)]
+	pub(crate) fn bool_to_integer<S>(value: &bool, serializer: S) -> StdResult<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		serializer.serialize_i64(i64::from(*value))
+	}
+
+	/// A custom serializer that converts an [`OffsetDateTime`] to a millisecond
+	/// Unix timestamp.
+	///
+	/// This is the counterpart of [`datetime_from_millis_timestamp`].
+	///
+	/// [`datetime_from_millis_timestamp`]: super::de::datetime_from_millis_timestamp
+	pub(crate) fn datetime_to_millis_timestamp<S>(
+		value: &OffsetDateTime,
+		serializer: S,
+	) -> StdResult<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		const NANOSECONDS_PER_MILLISECOND: i128 = 1_000_000;
+		let millis = i64::try_from(value.unix_timestamp_nanos() / NANOSECONDS_PER_MILLISECOND)
+			.map_err(S::Error::custom)?;
+		serializer.serialize_i64(millis)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	// Uses
+	use serde::Deserialize;
+
+	use super::{from_json_str, is_html_response};
+	use crate::error::SponsorBlockError;
+
+	#[derive(Debug, Deserialize)]
+	#[allow(dead_code)]
+	struct Outer {
+		name: String,
+		inner: Vec<Inner>,
+	}
+
+	#[derive(Debug, Deserialize)]
+	#[allow(dead_code)]
+	struct Inner {
+		value: u32,
+	}
+
+	#[test]
+	fn is_html_response_detects_html() {
+		// (content type, text, expected)
+		let cases = [
+			(Some("text/html; charset=utf-8"), "<!DOCTYPE html>", true),
+			(Some("text/html"), "not actually html", true),
+			(None, "<html><body>Bad Gateway</body></html>", true),
+			(Some("text/plain"), "\n  <html></html>", true),
+			(Some("application/json; charset=utf-8"), "[]", false),
+			(Some("application/json"), "  {\"a\": 1}", false),
+			(None, "Not Found", false),
+			(None, "", false),
+		];
+
+		for (content_type, text, expected) in cases {
+			assert_eq!(
+				is_html_response(content_type, text),
+				expected,
+				"content type: {content_type:?}, text: {text:?}"
+			);
+		}
+	}
+
+	#[derive(Debug, Deserialize)]
+	struct Time(#[serde(deserialize_with = "super::de::f32_from_number_or_str")] f32);
+
+	// The expected values are all exactly representable
+	#[allow(clippy::float_cmp)]
+	#[test]
+	fn f32_from_number_or_str_accepts_numbers() {
+		for (text, expected) in [("12", 12.0), ("12.5", 12.5), ("-0.25", -0.25)] {
+			assert_eq!(serde_json::from_str::<Time>(text).unwrap().0, expected);
+		}
+	}
+
+	// The expected values are all exactly representable
+	#[allow(clippy::float_cmp)]
+	#[test]
+	fn f32_from_number_or_str_accepts_numeric_strings() {
+		for (text, expected) in [
+			(r#""12""#, 12.0),
+			(r#""12.5""#, 12.5),
+			(r#"" 3.75 ""#, 3.75),
+			(r#""1e2""#, 100.0),
+		] {
+			assert_eq!(serde_json::from_str::<Time>(text).unwrap().0, expected);
+		}
+	}
+
+	#[test]
+	fn f32_from_number_or_str_rejects_invalid_values() {
+		for text in [r#""""#, r#""twelve""#, r#""12s""#, "true", "null", "[12]"] {
+			assert!(
+				serde_json::from_str::<Time>(text).is_err(),
+				"{text} was accepted"
+			);
+		}
+	}
+
+	#[test]
+	fn from_json_str_parses_valid_data() {
+		let outer: Outer =
+			from_json_str(r#"{"name": "a", "inner": [{"value": 1}, {"value": 2}]}"#).unwrap();
+		assert_eq!(outer.name, "a");
+		assert_eq!(outer.inner.len(), 2);
+	}
+
+	#[test]
+	fn from_json_str_reports_path_of_wrong_typed_nested_field() {
+		let error =
+			from_json_str::<Outer>(r#"{"name": "a", "inner": [{"value": 1}, {"value": "two"}]}"#)
+				.unwrap_err();
+		let SponsorBlockError::Deserialization(error) = error else {
+			panic!("unexpected error: {error:?}");
+		};
+		assert!(error.is_data());
+		assert!(
+			error.to_string().starts_with("inner[1].value: "),
+			"unexpected error message: {error}"
+		);
+	}
+
+	#[test]
+	fn from_json_str_keeps_syntax_errors_as_is() {
+		let error = from_json_str::<Outer>(r#"{"name": "a", "inner": ["#).unwrap_err();
+		let SponsorBlockError::Deserialization(error) = error else {
+			panic!("unexpected error: {error:?}");
+		};
+		assert!(error.is_eof());
+	}
+
+	#[cfg(all(feature = "mock", feature = "user"))]
+	#[tokio::test]
+	async fn html_success_bodies_are_unexpected_responses() {
+		let client = crate::Client::with_responder("test", |_: &str| {
+			(200, "<html><body>Bad Gateway</body></html>".to_owned())
+		});
+
+		let error = client.fetch_api_status().await.unwrap_err();
+		assert!(matches!(error, SponsorBlockError::UnexpectedResponse(_)));
+	}
+}
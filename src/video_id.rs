@@ -0,0 +1,132 @@
+//! Utilities for working with video IDs.
+
+// Uses
+use reqwest::Url;
+
+/// Extracts a YouTube video ID from user input.
+///
+/// Accepts raw video IDs, as well as most forms of YouTube URL:
+/// - `https://www.youtube.com/watch?v=<ID>` (including `m.` and `music.`)
+/// - `https://youtu.be/<ID>`
+/// - `https://www.youtube.com/shorts/<ID>`
+/// - `https://www.youtube.com/embed/<ID>` (including `youtube-nocookie.com`)
+/// - `https://www.youtube.com/live/<ID>`
+/// - `https://www.youtube.com/v/<ID>`
+///
+/// The scheme can be left out of URLs.
+///
+/// Returns [`None`] if a video ID can't be found, or if the one found isn't a
+/// valid YouTube video ID.
+#[must_use]
+pub fn parse_video_id<I>(input: I) -> Option<String>
+where
+	I: AsRef<str>,
+{
+	let input = input.as_ref().trim();
+	if is_valid_video_id(input) {
+		return Some(input.to_owned());
+	}
+
+	let url = if input.contains("://") {
+		Url::parse(input)
+	} else {
+		Url::parse(format!("https://{input}").as_str())
+	}
+	.ok()?;
+	let host = url.host_str()?;
+	let host = host.strip_prefix("www.").unwrap_or(host);
+	let mut path_segments = url.path_segments()?.filter(|s| !s.is_empty());
+
+	let video_id = match host {
+		"youtu.be" => path_segments.next()?.to_owned(),
+		"youtube.com" | "m.youtube.com" | "music.youtube.com" | "youtube-nocookie.com" => {
+			match path_segments.next()? {
+				"watch" => url
+					.query_pairs()
+					.find(|(key, _)| key == "v")
+					.map(|(_, value)| value.into_owned())?,
+				"shorts" | "embed" | "live" | "v" => path_segments.next()?.to_owned(),
+				_ => return None,
+			}
+		}
+		_ => return None,
+	};
+
+	is_valid_video_id(video_id.as_str()).then_some(video_id)
+}
+
+/// Checks whether a string has the shape of a YouTube video ID.
+fn is_valid_video_id(video_id: &str) -> bool {
+	const LENGTH: usize = 11;
+
+	video_id.len() == LENGTH
+		&& video_id
+			.bytes()
+			.all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
+
+#[cfg(test)]
+mod tests {
+	// Uses
+	use super::parse_video_id;
+
+	#[test]
+	fn parse_video_id_inputs() {
+		const ID: &str = "dQw4w9WgXcQ";
+
+		let cases = [
+			// Bare IDs
+			("dQw4w9WgXcQ", Some(ID)),
+			("  dQw4w9WgXcQ\n", Some(ID)),
+			("a-b_c-d_e-f", Some("a-b_c-d_e-f")),
+			// Watch URLs
+			("https://www.youtube.com/watch?v=dQw4w9WgXcQ", Some(ID)),
+			("http://youtube.com/watch?v=dQw4w9WgXcQ", Some(ID)),
+			("www.youtube.com/watch?v=dQw4w9WgXcQ", Some(ID)),
+			("https://m.youtube.com/watch?v=dQw4w9WgXcQ", Some(ID)),
+			("https://music.youtube.com/watch?v=dQw4w9WgXcQ", Some(ID)),
+			(
+				"https://www.youtube.com/watch?feature=share&v=dQw4w9WgXcQ&t=42",
+				Some(ID),
+			),
+			("https://www.youtube.com/watch?v=dQw4w9WgXcQ#t=42", Some(ID)),
+			// Short links
+			("https://youtu.be/dQw4w9WgXcQ", Some(ID)),
+			("youtu.be/dQw4w9WgXcQ?t=42", Some(ID)),
+			// Shorts, embeds and the rest
+			("https://www.youtube.com/shorts/dQw4w9WgXcQ", Some(ID)),
+			(
+				"https://www.youtube.com/embed/dQw4w9WgXcQ?start=42",
+				Some(ID),
+			),
+			(
+				"https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ",
+				Some(ID),
+			),
+			("https://www.youtube.com/live/dQw4w9WgXcQ", Some(ID)),
+			("https://www.youtube.com/v/dQw4w9WgXcQ", Some(ID)),
+			// Invalid inputs
+			("", None),
+			("not a video", None),
+			("dQw4w9WgXc", None),
+			("dQw4w9WgXcQQ", None),
+			("dQw4w9WgXc!", None),
+			// Near misses
+			("https://www.youtube.com/watch", None),
+			("https://www.youtube.com/watch?v=", None),
+			("https://www.youtube.com/watch?v=dQw4w9WgXc", None),
+			("https://www.youtube.com/watch?vi=dQw4w9WgXcQ", None),
+			("https://www.youtube.com/shorts/", None),
+			("https://www.youtube.com/playlist?list=dQw4w9WgXcQ", None),
+			("https://www.youtube.com/channel/dQw4w9WgXcQ", None),
+			("https://youtu.be/", None),
+			("https://vimeo.com/dQw4w9WgXcQ", None),
+			("https://notyoutube.com/watch?v=dQw4w9WgXcQ", None),
+			("https://youtube.com.example.com/watch?v=dQw4w9WgXcQ", None),
+		];
+
+		for (input, expected) in cases {
+			assert_eq!(parse_video_id(input).as_deref(), expected, "{input:?}");
+		}
+	}
+}